@@ -0,0 +1,50 @@
+// 启用 embed-resources 特性时，把 resources/ 下所有文件打包成 OUT_DIR/resources.zip，
+// 由 main 通过 include_bytes! 嵌入可执行文件
+fn main() {
+    #[cfg(feature = "embed-resources")]
+    embed::zip_resources().expect("failed to bundle resources");
+}
+
+#[cfg(feature = "embed-resources")]
+mod embed {
+    use std::fs::{self, File};
+    use std::io::{self, Write};
+    use std::path::{Path, PathBuf};
+
+    use zip::write::{FileOptions, ZipWriter};
+    use zip::CompressionMethod;
+
+    pub fn zip_resources() -> zip::result::ZipResult<()> {
+        println!("cargo:rerun-if-changed=resources");
+        let out = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR not set")).join("resources.zip");
+        let mut zip = ZipWriter::new(File::create(out)?);
+        // ggez 读取 zip 时未启用压缩算法，只能存储不压缩
+        let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+        let root = Path::new("resources");
+        for path in files(root)? {
+            let name = path.strip_prefix(root).expect("file under resources/").to_string_lossy().replace('\\', "/");
+            zip.start_file(name, options)?;
+            zip.write_all(&fs::read(&path)?)?;
+        }
+        zip.finish()?;
+        Ok(())
+    }
+
+    // 递归列出目录下的文件（按路径排序，保证构建结果稳定）
+    fn files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut result = Vec::new();
+        if !dir.is_dir() {
+            return Ok(result);
+        }
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                result.extend(files(&path)?);
+            } else {
+                result.push(path);
+            }
+        }
+        result.sort();
+        Ok(result)
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use ggez::graphics::spritebatch::SpriteBatch;
+use ggez::graphics::{self, DrawParam, Image, Rect};
+use ggez::{filesystem, Context, GameResult};
+
+use crate::atlas::{self, Manifest};
+use crate::level::Level;
+
+// 图片名 -> 资源路径（相对 resources/）
+const IMAGES: [(&str, &str); 8] = [
+    ("tile", "/stock.png"),
+    ("player", "/player.png"),
+    ("special", "/special_block.png"),
+    ("coin", "/coin.png"),
+    // 怪物素材
+    ("monster", "/boast.png"),
+    ("menu", "/menu_bg.png"),
+    ("pipe", "/pipe.png"),
+    ("bg", "/bg.png"),
+];
+
+const ATLAS_IMAGE: &str = "/atlas.png";
+const ATLAS_MANIFEST: &str = "/atlas.toml";
+
+// 检查文件修改时间的间隔（秒）
+const POLL_SECS: f32 = 1.0;
+
+// 缺失图片的占位：品红/黑色棋盘格
+fn placeholder(ctx: &mut Context) -> GameResult<Image> {
+    const SIZE: u16 = 16;
+    let mut rgba = Vec::with_capacity(SIZE as usize * SIZE as usize * 4);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let magenta = (x / 4 + y / 4) % 2 == 0;
+            rgba.extend_from_slice(if magenta { &[255, 0, 255, 255] } else { &[0, 0, 0, 255] });
+        }
+    }
+    Image::from_rgba8(ctx, SIZE, SIZE, &rgba)
+}
+
+// 图片中的一块区域（图集中的子矩形，或整张图片）
+#[derive(Clone, Copy)]
+pub struct Sprite<'a> {
+    image: &'a Image,
+    // 图集中的精灵共用同一个 sheet，可合并绘制
+    sheet: &'a str,
+    // 归一化的源矩形
+    src: Rect,
+    // 像素尺寸
+    w: f32,
+    h: f32,
+}
+
+impl<'a> Sprite<'a> {
+    pub fn image(&self) -> &'a Image {
+        self.image
+    }
+
+    // 绘制到 (x, y)，缩放到 w x h
+    pub fn at(&self, x: f32, y: f32, w: f32, h: f32) -> DrawParam {
+        DrawParam::default().src(self.src).dest([x, y]).scale([w / self.w, h / self.h])
+    }
+}
+
+// 按顺序收集要绘制的精灵，连续来自同一张图片的合并为一个 SpriteBatch 绘制
+#[derive(Default)]
+pub struct SpriteList<'a> {
+    items: Vec<(Sprite<'a>, DrawParam)>,
+}
+
+impl<'a> SpriteList<'a> {
+    pub fn add(&mut self, sprite: Sprite<'a>, param: DrawParam) {
+        self.items.push((sprite, param));
+    }
+
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        for run in self.items.chunk_by(|a, b| a.0.sheet == b.0.sheet) {
+            let mut batch = SpriteBatch::new(run[0].0.image.clone());
+            for (_, param) in run {
+                batch.add(*param);
+            }
+            graphics::draw(ctx, &batch, DrawParam::default())?;
+        }
+        Ok(())
+    }
+}
+
+// 按名字管理的图片资源与关卡文件；缺失时用占位图/内置关卡并打印警告，
+// 运行中定期检查 resources/ 下文件的修改时间并重新加载。
+// 存在 atlas.png/atlas.toml 时精灵从图集中取子矩形，但打包后改过或缺失的图片仍画单独的图片
+pub struct Assets {
+    images: HashMap<&'static str, Image>,
+    // 成功加载的图片文件内容的哈希，与图集清单中的比较
+    hashes: HashMap<&'static str, u32>,
+    atlas: Option<(Image, Manifest)>,
+    placeholder: Image,
+    // 磁盘上的资源目录（用于检查修改时间）
+    resource_dir: PathBuf,
+    mtimes: HashMap<String, SystemTime>,
+    // 命令行 --level 指定的关卡文件及其修改时间：热重载时从该文件而不是 resources/levels/ 读取
+    level_file: Option<(PathBuf, Option<SystemTime>)>,
+    poll_timer: f32,
+}
+
+impl Assets {
+    pub fn new(ctx: &mut Context, resource_dir: PathBuf) -> GameResult<Self> {
+        let placeholder = placeholder(ctx)?;
+        let mut assets = Self { images: HashMap::new(), hashes: HashMap::new(), atlas: None, placeholder, resource_dir, mtimes: HashMap::new(), level_file: None, poll_timer: 0.0 };
+        for (name, path) in IMAGES {
+            assets.load_image(ctx, name, path);
+        }
+        assets.load_atlas(ctx);
+        Ok(assets)
+    }
+
+    // 图集是可选的：没有时各精灵使用单独的图片
+    fn load_atlas(&mut self, ctx: &mut Context) {
+        self.remember(ATLAS_IMAGE);
+        self.remember(ATLAS_MANIFEST);
+        let mut text = String::new();
+        if filesystem::open(ctx, ATLAS_MANIFEST).and_then(|mut file| Ok(file.read_to_string(&mut text)?)).is_err() {
+            self.atlas = None;
+            return;
+        }
+        let manifest: Manifest = match toml::from_str(&text) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                eprintln!("invalid atlas manifest {}: {}", ATLAS_MANIFEST, e);
+                self.atlas = None;
+                return;
+            }
+        };
+        match Image::new(ctx, ATLAS_IMAGE) {
+            Ok(image) => {
+                for (name, _) in atlas::SPRITES {
+                    match manifest.regions.get(name) {
+                        None => eprintln!("atlas has no region for {}, drawing it from its own image", name),
+                        Some(r) if self.hashes.get(name).is_some_and(|h| *h != r.hash) => {
+                            eprintln!("atlas is out of date for {}, drawing it from its own image (run `cargo run --bin atlas`)", name)
+                        }
+                        _ => {}
+                    }
+                }
+                self.atlas = Some((image, manifest));
+            }
+            Err(e) => {
+                eprintln!("cannot load {} ({}), using separate images", ATLAS_IMAGE, e);
+                self.atlas = None;
+            }
+        }
+    }
+
+    fn load_image(&mut self, ctx: &mut Context, name: &'static str, path: &str) {
+        let mut bytes = Vec::new();
+        let loaded = filesystem::open(ctx, path)
+            .and_then(|mut file| Ok(file.read_to_end(&mut bytes)?))
+            .and_then(|_| Image::from_bytes(ctx, &bytes));
+        match loaded {
+            Ok(image) => {
+                self.images.insert(name, image);
+                self.hashes.insert(name, atlas::hash(&bytes));
+            }
+            Err(e) => {
+                eprintln!("missing image {} ({}), using placeholder", path, e);
+                self.images.insert(name, self.placeholder.clone());
+                self.hashes.remove(name);
+            }
+        }
+        self.remember(path);
+    }
+
+    pub fn image(&self, name: &str) -> &Image {
+        self.images.get(name).unwrap_or(&self.placeholder)
+    }
+
+    // 精灵：图集中的区域与当前图片文件一致时取图集，否则为整张单独的图片（或占位图）
+    pub fn sprite<'a>(&'a self, name: &'a str) -> Sprite<'a> {
+        if let Some((image, manifest)) = &self.atlas
+            && let Some(r) = manifest.regions.get(name)
+            && self.hashes.get(name) == Some(&r.hash)
+        {
+            let (aw, ah) = (manifest.width as f32, manifest.height as f32);
+            return Sprite {
+                image,
+                sheet: ATLAS_IMAGE,
+                src: Rect::new(r.x as f32 / aw, r.y as f32 / ah, r.w as f32 / aw, r.h as f32 / ah),
+                w: r.w as f32,
+                h: r.h as f32,
+            };
+        }
+        let image = self.image(name);
+        Sprite { image, sheet: name, src: Rect::one(), w: image.width() as f32, h: image.height() as f32 }
+    }
+
+    // 读取 resources/levels/<id>.toml；文件不存在时使用内置关卡，格式错误时警告并回退。
+    // 之后热重载监视该文件（不再监视 --level 指定的文件）
+    pub fn level(&mut self, ctx: &mut Context, id: &str) -> Level {
+        self.level_file = None;
+        let path = format!("/levels/{}.toml", id);
+        self.remember(&path);
+        let mut text = String::new();
+        if filesystem::open(ctx, &path).and_then(|mut file| Ok(file.read_to_string(&mut text)?)).is_err() {
+            return Level::builtin();
+        }
+        toml::from_str(&text).unwrap_or_else(|e| {
+            eprintln!("invalid level file {}: {}", path, e);
+            Level::builtin()
+        })
+    }
+
+    // 当前关卡来自 --level 指定的文件：热重载监视该文件
+    pub fn watch_level_file(&mut self, path: &Path) {
+        self.level_file = Some((path.to_path_buf(), file_modified(path)));
+    }
+
+    fn modified(&self, path: &str) -> Option<SystemTime> {
+        file_modified(&self.resource_dir.join(path.trim_start_matches('/')))
+    }
+
+    fn remember(&mut self, path: &str) {
+        if let Some(time) = self.modified(path) {
+            self.mtimes.insert(path.to_string(), time);
+        }
+    }
+
+    fn changed(&self, path: &str) -> bool {
+        let time = self.modified(path);
+        time.is_some() && time != self.mtimes.get(path).copied()
+    }
+
+    // 每帧调用：重新加载有改动的图片；当前关卡的文件有改动时返回重新读取的关卡
+    pub fn poll(&mut self, ctx: &mut Context, dt: f32, level_id: &str) -> Option<Level> {
+        self.poll_timer += dt;
+        if self.poll_timer < POLL_SECS {
+            return None;
+        }
+        self.poll_timer = 0.0;
+        for (name, path) in IMAGES {
+            if self.changed(path) {
+                println!("reloading {}", path);
+                self.load_image(ctx, name, path);
+            }
+        }
+        if self.changed(ATLAS_IMAGE) || self.changed(ATLAS_MANIFEST) {
+            println!("reloading atlas");
+            self.load_atlas(ctx);
+        }
+        if let Some((path, time)) = &mut self.level_file {
+            let modified = file_modified(path);
+            if modified.is_none() || modified == *time {
+                return None;
+            }
+            *time = modified;
+            println!("reloading {}", path.display());
+            // 改到一半的文件可能暂时无法解析，保留当前关卡等下一次保存
+            return Level::load(path).map_err(|e| eprintln!("{}", e)).ok();
+        }
+        let path = format!("/levels/{}.toml", level_id);
+        if !self.changed(&path) {
+            return None;
+        }
+        println!("reloading {}", path);
+        Some(self.level(ctx, level_id))
+    }
+}
+
+fn file_modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
@@ -0,0 +1,37 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+// 打包进图集的精灵：名字 -> resources/ 下的文件
+pub const SPRITES: [(&str, &str); 6] = [
+    ("tile", "stock.png"),
+    ("player", "player.png"),
+    ("special", "special_block.png"),
+    ("coin", "coin.png"),
+    ("monster", "boast.png"),
+    ("pipe", "pipe.png"),
+];
+
+// 图集中一个精灵的像素区域
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+    // 打包时源 PNG 的哈希；与当前文件不一致说明图集已过时
+    pub hash: u32,
+}
+
+// 文件内容的 FNV-1a 哈希（32 位，TOML 整数放得下）
+pub fn hash(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |h, b| (h ^ *b as u32).wrapping_mul(0x0100_0193))
+}
+
+// resources/atlas.toml：图集尺寸与各精灵区域（由 `cargo run --bin atlas` 生成）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub width: u32,
+    pub height: u32,
+    pub regions: BTreeMap<String, Region>,
+}
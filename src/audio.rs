@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use ggez::audio::{SoundData, SoundSource, Source};
+use ggez::{filesystem, timer, Context};
+
+// 同一音效最多同时播放的实例数，超出时停掉最早的一个
+const MAX_INSTANCES: usize = 3;
+// 切换背景音乐时的交叉淡入淡出时长（秒）
+const CROSSFADE_SECS: f32 = 1.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sfx {
+    Jump,
+    Coin,
+    Bump,
+    Stomp,
+    Death,
+    PipeEnter,
+    Victory,
+    // 剩余时间不多
+    TimeWarning,
+    OneUp,
+}
+
+impl Sfx {
+    pub const ALL: [Sfx; 9] = [Sfx::Jump, Sfx::Coin, Sfx::Bump, Sfx::Stomp, Sfx::Death, Sfx::PipeEnter, Sfx::Victory, Sfx::TimeWarning, Sfx::OneUp];
+
+    fn path(self) -> &'static str {
+        match self {
+            Sfx::Jump => "/sounds/jump.wav",
+            Sfx::Coin => "/sounds/coin.wav",
+            Sfx::Bump => "/sounds/bump.wav",
+            Sfx::Stomp => "/sounds/stomp.wav",
+            Sfx::Death => "/sounds/death.wav",
+            Sfx::PipeEnter => "/sounds/pipe.wav",
+            Sfx::Victory => "/sounds/victory.wav",
+            Sfx::TimeWarning => "/sounds/warning.wav",
+            Sfx::OneUp => "/sounds/1up.wav",
+        }
+    }
+}
+
+// 背景音乐（按界面选择）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Music {
+    Menu,
+    Level,
+}
+
+impl Music {
+    pub const ALL: [Music; 2] = [Music::Menu, Music::Level];
+
+    fn path(self) -> &'static str {
+        match self {
+            Music::Menu => "/music/menu.wav",
+            Music::Level => "/music/level.wav",
+        }
+    }
+}
+
+// 音量分类，分别由选项界面的 Music / SFX 控制
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    Music,
+    Sfx,
+}
+
+// 音频系统：没有音频设备（如无头 CI）或缺少声音文件时静默运行
+pub struct Audio {
+    enabled: bool,
+    music_volume: f32,
+    sfx_volume: f32,
+    sounds: HashMap<Sfx, SoundData>,
+    voices: HashMap<Sfx, Vec<Source>>,
+    tracks: HashMap<Music, SoundData>,
+    // 当前（淡入中的）音乐与正在淡出的上一首
+    current: Option<Music>,
+    music: Option<Source>,
+    fading: Option<Source>,
+    fade: f32,
+}
+
+impl Audio {
+    pub fn new(ctx: &mut Context, enabled: bool, music_volume: f32, sfx_volume: f32) -> Self {
+        let mut audio = Self {
+            enabled,
+            music_volume,
+            sfx_volume,
+            sounds: HashMap::new(),
+            voices: HashMap::new(),
+            tracks: HashMap::new(),
+            current: None,
+            music: None,
+            fading: None,
+            fade: 1.0,
+        };
+        if !enabled {
+            return audio;
+        }
+        for sfx in Sfx::ALL {
+            if let Some(data) = load(ctx, sfx.path()) {
+                audio.sounds.insert(sfx, data);
+            }
+        }
+        for track in Music::ALL {
+            if let Some(data) = load(ctx, track.path()) {
+                audio.tracks.insert(track, data);
+            }
+        }
+        audio
+    }
+
+    pub fn set_volume(&mut self, category: Category, volume: f32) {
+        match category {
+            Category::Music => self.music_volume = volume,
+            Category::Sfx => self.sfx_volume = volume,
+        }
+    }
+
+    pub fn play(&mut self, ctx: &mut Context, sfx: Sfx) {
+        let Some(data) = self.sounds.get(&sfx) else {
+            return;
+        };
+        let voices = self.voices.entry(sfx).or_default();
+        voices.retain(|v| !v.stopped());
+        if voices.len() >= MAX_INSTANCES {
+            let mut oldest = voices.remove(0);
+            let _ = oldest.stop(ctx);
+        }
+        if let Ok(mut source) = Source::from_data(ctx, data.clone()) {
+            source.set_volume(self.sfx_volume);
+            if source.play(ctx).is_ok() {
+                voices.push(source);
+            }
+        }
+    }
+
+    // 每帧调用：切换到当前界面的音乐并推进交叉淡入淡出
+    pub fn update(&mut self, ctx: &mut Context, music: Option<Music>) {
+        if !self.enabled {
+            return;
+        }
+        if music != self.current {
+            self.current = music;
+            if let Some(mut old) = self.fading.take() {
+                let _ = old.stop(ctx);
+            }
+            self.fading = self.music.take();
+            self.fade = 0.0;
+            if let Some(data) = music.and_then(|m| self.tracks.get(&m))
+                && let Ok(mut source) = Source::from_data(ctx, data.clone())
+            {
+                source.set_repeat(true);
+                source.set_volume(0.0);
+                if source.play(ctx).is_ok() {
+                    self.music = Some(source);
+                }
+            }
+        }
+
+        self.fade = (self.fade + timer::delta(ctx).as_secs_f32() / CROSSFADE_SECS).min(1.0);
+        if let Some(source) = &mut self.music {
+            source.set_volume(self.music_volume * self.fade);
+        }
+        if let Some(old) = &mut self.fading {
+            old.set_volume(self.music_volume * (1.0 - self.fade));
+        }
+        if self.fade >= 1.0
+            && let Some(mut old) = self.fading.take()
+        {
+            let _ = old.stop(ctx);
+        }
+    }
+}
+
+// 读取声音文件；缺失或无法解码时给出警告并返回 None
+fn load(ctx: &mut Context, path: &str) -> Option<SoundData> {
+    if !filesystem::exists(ctx, path) {
+        eprintln!("sound {} not found, it will be silent", path);
+        return None;
+    }
+    match SoundData::new(ctx, path) {
+        Ok(data) if data.can_play() => Some(data),
+        Ok(_) => {
+            eprintln!("sound {} could not be decoded, it will be silent", path);
+            None
+        }
+        Err(e) => {
+            eprintln!("failed to load sound {}: {}", path, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{Music, Sfx};
+
+    // 缺少声音文件时游戏仍能运行但没有声音，这里确保随仓库提交了所有文件
+    #[test]
+    fn every_sound_is_shipped() {
+        let resources = Path::new(env!("CARGO_MANIFEST_DIR")).join("resources");
+        let paths = Sfx::ALL.map(Sfx::path).into_iter().chain(Music::ALL.map(Music::path));
+        for path in paths {
+            assert!(resources.join(path.trim_start_matches('/')).is_file(), "resources{} is missing", path);
+        }
+    }
+}
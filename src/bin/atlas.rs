@@ -0,0 +1,70 @@
+// 图集打包工具：cargo run --bin atlas [resources 目录]
+// 把精灵 PNG 打包成 atlas.png，并写出区域清单 atlas.toml（游戏启动时读取）
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use image::{GenericImage, RgbaImage};
+
+use game::atlas::{self, Manifest, Region, SPRITES};
+
+// 精灵之间的间隔；边缘像素向外复制一圈，避免线性过滤时采样到相邻精灵
+const PADDING: u32 = 2;
+const MAX_WIDTH: u32 = 1024;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let dir = PathBuf::from(std::env::args().nth(1).unwrap_or_else(|| "resources".to_string()));
+    let mut sprites = Vec::new();
+    for (name, file) in SPRITES {
+        let bytes = fs::read(dir.join(file)).map_err(|e| format!("{}: {}", file, e))?;
+        let image = image::load_from_memory(&bytes).map_err(|e| format!("{}: {}", file, e))?.to_rgba8();
+        sprites.push((name, image, atlas::hash(&bytes)));
+    }
+
+    // 按高度从高到低逐行（shelf）摆放
+    sprites.sort_by_key(|(_, image, _)| std::cmp::Reverse(image.height()));
+    let mut regions = Vec::new();
+    let (mut x, mut y, mut shelf_h, mut used_w) = (PADDING, PADDING, 0, 0);
+    for (name, image, hash) in &sprites {
+        let (w, h) = image.dimensions();
+        if w + PADDING * 2 > MAX_WIDTH {
+            return Err(format!("{} is wider than the atlas ({}px)", name, MAX_WIDTH).into());
+        }
+        if x + w + PADDING > MAX_WIDTH {
+            x = PADDING;
+            y += shelf_h + PADDING;
+            shelf_h = 0;
+        }
+        regions.push((name.to_string(), Region { x, y, w, h, hash: *hash }));
+        x += w + PADDING;
+        shelf_h = shelf_h.max(h);
+        used_w = used_w.max(x);
+    }
+    let width = used_w.next_power_of_two();
+    let height = (y + shelf_h + PADDING).next_power_of_two();
+
+    let mut atlas = RgbaImage::new(width, height);
+    for ((_, image, _), (_, region)) in sprites.iter().zip(&regions) {
+        atlas.copy_from(image, region.x, region.y)?;
+        extrude(&mut atlas, region);
+    }
+    atlas.save(dir.join("atlas.png"))?;
+
+    let manifest = Manifest { width, height, regions: regions.into_iter().collect() };
+    fs::write(dir.join("atlas.toml"), toml::to_string(&manifest)?)?;
+    println!("packed {} sprites into {}x{} atlas", sprites.len(), width, height);
+    Ok(())
+}
+
+// 把区域四边的像素向外复制一像素
+fn extrude(atlas: &mut RgbaImage, r: &Region) {
+    let (x1, y1) = (r.x + r.w - 1, r.y + r.h - 1);
+    for x in r.x..=x1 {
+        atlas.put_pixel(x, r.y - 1, *atlas.get_pixel(x, r.y));
+        atlas.put_pixel(x, y1 + 1, *atlas.get_pixel(x, y1));
+    }
+    for y in r.y - 1..=y1 + 1 {
+        atlas.put_pixel(r.x - 1, y, *atlas.get_pixel(r.x, y));
+        atlas.put_pixel(x1 + 1, y, *atlas.get_pixel(x1, y));
+    }
+}
@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+usage: game [options]
+
+  --level <file>          play the level in <file> instead of resources/levels/1-1.toml
+                          (with --replay: instead of the level the replay was recorded on)
+  --skip-menu             start playing immediately
+  --window <W>x<H>        window size in pixels (overrides the saved scale)
+  --fullscreen            start in fullscreen (not saved to settings)
+  --replay <file>         play back a recorded replay
+  --seed <n>              seed for the random effects (particles); the simulation itself
+                          is deterministic, so it has no effect without a window
+  --headless              run the simulation without a window and print the result
+  --frames <n>            with --headless: stop after n steps (60 per second)
+  --verify-replay <file>  re-simulate a replay and check its score and state hash
+  --help                  show this help";
+
+// 命令行参数（手写解析，避免引入额外依赖）
+#[derive(Debug, Default)]
+pub struct Args {
+    pub level: Option<PathBuf>,
+    pub skip_menu: bool,
+    pub window: Option<(f32, f32)>,
+    pub fullscreen: bool,
+    pub replay: Option<PathBuf>,
+    pub seed: Option<u32>,
+    pub headless: bool,
+    pub frames: Option<u64>,
+    pub verify_replay: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Args {
+    // args 不含程序名
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--level" => parsed.level = Some(value()?.into()),
+                "--skip-menu" => parsed.skip_menu = true,
+                "--window" => {
+                    let text = value()?;
+                    let size = text.split_once('x').and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)));
+                    match size {
+                        Some((w, h)) if w >= 1.0 && h >= 1.0 => parsed.window = Some((w, h)),
+                        _ => return Err(format!("invalid window size: {} (expected e.g. 1600x800)", text)),
+                    }
+                }
+                "--fullscreen" => parsed.fullscreen = true,
+                "--replay" => parsed.replay = Some(value()?.into()),
+                "--seed" => {
+                    let text = value()?;
+                    parsed.seed = Some(text.parse().map_err(|_| format!("invalid seed: {}", text))?);
+                }
+                "--headless" => parsed.headless = true,
+                "--frames" => {
+                    let text = value()?;
+                    parsed.frames = Some(text.parse().map_err(|_| format!("invalid frame count: {}", text))?);
+                }
+                "--verify-replay" => parsed.verify_replay = Some(value()?.into()),
+                "--help" | "-h" => parsed.help = true,
+                _ => return Err(format!("unknown argument: {}", arg)),
+            }
+        }
+        if parsed.frames.is_some() && !parsed.headless {
+            return Err("--frames only applies to --headless".to_string());
+        }
+        // 种子只影响粒子效果，无窗口时没有可影响的东西
+        if parsed.seed.is_some() && (parsed.headless || parsed.verify_replay.is_some()) {
+            return Err("--seed only affects particle effects and cannot be used with --headless or --verify-replay".to_string());
+        }
+        Ok(parsed)
+    }
+}
@@ -0,0 +1,154 @@
+use ggez::graphics::Rect;
+use ggez::input::mouse;
+use ggez::Context;
+
+use crate::console::{Commands, FnCommand};
+use crate::save::Power;
+use crate::scene::{Screen, Transition};
+use crate::world::{Monster, COIN_SIZE, TILE_SIZE};
+use crate::{to_virtual, GameState};
+
+// 游戏本身的开发者命令
+pub fn register(commands: &mut Commands<GameState>) {
+    let builtin: [FnCommand<GameState>; 9] = [
+        FnCommand { name: "tp", usage: "tp [col row]  teleport player 1 to a tile (default: cursor)", run: teleport },
+        FnCommand { name: "god", usage: "god  toggle invincibility", run: god },
+        FnCommand { name: "noclip", usage: "noclip  toggle flying through tiles (jump = up, run = down)", run: noclip },
+        FnCommand { name: "spawn", usage: "spawn <monster|coin>  spawn at the cursor", run: spawn },
+        FnCommand { name: "give", usage: "give <small|big|fire>  set the power-up", run: give },
+        FnCommand { name: "score", usage: "score <n>  set the score", run: score },
+        FnCommand { name: "level", usage: "level <id>  load resources/levels/<id>.toml", run: level },
+        FnCommand { name: "slowmo", usage: "slowmo [factor]  toggle slow motion (default 0.25)", run: slowmo },
+        FnCommand { name: "dump", usage: "dump  print the world state to stdout", run: dump },
+    ];
+    for command in builtin {
+        commands.register(Box::new(command));
+    }
+}
+
+fn parse<T: std::str::FromStr>(arg: Option<&&str>, what: &str) -> Result<T, String> {
+    let arg = arg.ok_or_else(|| format!("missing {}", what))?;
+    arg.parse().map_err(|_| format!("invalid {}: {}", what, arg))
+}
+
+// 鼠标位置（虚拟坐标）
+fn cursor(ctx: &Context) -> (f32, f32) {
+    let pos = mouse::position(ctx);
+    to_virtual(ctx, pos.x, pos.y)
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on { "on" } else { "off" }
+}
+
+fn teleport(game: &mut GameState, ctx: &mut Context, args: &[&str]) -> Result<String, String> {
+    let (x, y) = if args.is_empty() {
+        cursor(ctx)
+    } else {
+        let col: f32 = parse(args.first(), "col")?;
+        let row: f32 = parse(args.get(1), "row")?;
+        (col * TILE_SIZE + TILE_SIZE / 2.0, game.world.level_offset_y + row * TILE_SIZE + TILE_SIZE / 2.0)
+    };
+    let player = game.world.players.first_mut().ok_or("no player")?;
+    // 以玩家中心对齐目标点
+    player.x = x - player.w / 2.0;
+    player.y = y - player.h / 2.0;
+    player.vx = 0.0;
+    player.vy = 0.0;
+    game.stop_tracking();
+    Ok(format!("teleported to ({:.0}, {:.0})", x, y))
+}
+
+fn god(game: &mut GameState, _ctx: &mut Context, _args: &[&str]) -> Result<String, String> {
+    game.world.cheats.god = !game.world.cheats.god;
+    game.stop_tracking();
+    Ok(format!("god mode {}", on_off(game.world.cheats.god)))
+}
+
+fn noclip(game: &mut GameState, _ctx: &mut Context, _args: &[&str]) -> Result<String, String> {
+    game.world.cheats.noclip = !game.world.cheats.noclip;
+    game.stop_tracking();
+    Ok(format!("noclip {}", on_off(game.world.cheats.noclip)))
+}
+
+fn spawn(game: &mut GameState, ctx: &mut Context, args: &[&str]) -> Result<String, String> {
+    let (x, y) = cursor(ctx);
+    match args.first().copied() {
+        Some("monster") => {
+            // 与关卡中的怪物相同：24x24，在出生点左右各 3 格内巡逻
+            game.world.monsters.push(Monster {
+                x: x - 12.0,
+                y: y - 12.0,
+                w: 24.0,
+                h: 24.0,
+                vx: 60.0,
+                range_min: x - TILE_SIZE * 3.0,
+                range_max: x + TILE_SIZE * 3.0,
+            });
+        }
+        Some("coin") => {
+            let col = (x / TILE_SIZE).max(0.0) as usize;
+            let row = ((y - game.world.level_offset_y) / TILE_SIZE).max(0.0) as usize;
+            let rect = Rect::new(x - COIN_SIZE / 2.0, y - COIN_SIZE / 2.0, COIN_SIZE, COIN_SIZE);
+            game.world.coins.push((rect, (col, row)));
+        }
+        _ => return Err("usage: spawn <monster|coin>".to_string()),
+    }
+    game.stop_tracking();
+    Ok(format!("spawned {} at ({:.0}, {:.0})", args[0], x, y))
+}
+
+fn give(game: &mut GameState, _ctx: &mut Context, args: &[&str]) -> Result<String, String> {
+    game.world.power = match args.first().copied() {
+        Some("small") => Power::Small,
+        Some("big") => Power::Big,
+        Some("fire") => Power::Fire,
+        _ => return Err("usage: give <small|big|fire>".to_string()),
+    };
+    game.stop_tracking();
+    Ok(format!("power set to {:?}", game.world.power))
+}
+
+fn score(game: &mut GameState, _ctx: &mut Context, args: &[&str]) -> Result<String, String> {
+    game.world.score = parse(args.first(), "score")?;
+    game.stop_tracking();
+    Ok(format!("score set to {}", game.world.score))
+}
+
+// 读取关卡文件并从头开始（作弊开关随新的一局清空）
+fn level(game: &mut GameState, ctx: &mut Context, args: &[&str]) -> Result<String, String> {
+    let id = *args.first().ok_or("usage: level <id>")?;
+    let level = game.assets.level(ctx, id);
+    // 文件不存在时 assets 会回退到内置关卡
+    if level.id != id {
+        return Err(format!("no level {}", id));
+    }
+    game.set_level(ctx, level);
+    if game.scenes.top() != Screen::Playing {
+        game.scenes.reset(Screen::Playing, Transition::Fade);
+    }
+    Ok(format!("loaded level {} ({})", game.level.id, game.level.name))
+}
+
+fn slowmo(game: &mut GameState, _ctx: &mut Context, args: &[&str]) -> Result<String, String> {
+    game.time_scale = if !args.is_empty() {
+        let factor: f32 = parse(args.first(), "factor")?;
+        if factor <= 0.0 || factor > 1.0 {
+            return Err("factor must be in (0, 1]".to_string());
+        }
+        factor
+    } else if game.time_scale < 1.0 {
+        1.0
+    } else {
+        0.25
+    };
+    // 慢动作下速通计时不再可比
+    game.speedrun.stop();
+    Ok(format!("time scale {}", game.time_scale))
+}
+
+fn dump(game: &mut GameState, _ctx: &mut Context, _args: &[&str]) -> Result<String, String> {
+    let text = game.world.snapshot().map_err(|e| e.to_string())?;
+    println!("{}", text);
+    Ok(format!("dumped {} bytes of state to stdout (score {}, time {:.1})", text.len(), game.world.score, game.world.level_time))
+}
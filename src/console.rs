@@ -0,0 +1,167 @@
+use ggez::graphics::{self, Color, DrawMode, DrawParam, Rect};
+use ggez::{Context, GameResult};
+
+// 保留的输出行数与屏幕上显示的行数
+const MAX_LINES: usize = 200;
+const VISIBLE_LINES: usize = 12;
+const LINE_H: f32 = 18.0;
+// 下拉/收起动画时长（秒）
+const SLIDE_SECS: f32 = 0.15;
+
+// 控制台命令：各子系统实现该 trait 并注册到 Commands 中
+pub trait Command<T> {
+    fn name(&self) -> &'static str;
+    // 一行用法说明，由 help 列出
+    fn usage(&self) -> &'static str;
+    // 成功时返回要显示的输出，失败时返回错误信息
+    fn run(&self, target: &mut T, ctx: &mut Context, args: &[&str]) -> Result<String, String>;
+}
+
+// 由普通函数实现的命令，简单命令不必单独定义类型
+pub struct FnCommand<T> {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub run: fn(&mut T, &mut Context, &[&str]) -> Result<String, String>,
+}
+
+impl<T> Command<T> for FnCommand<T> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn usage(&self) -> &'static str {
+        self.usage
+    }
+
+    fn run(&self, target: &mut T, ctx: &mut Context, args: &[&str]) -> Result<String, String> {
+        (self.run)(target, ctx, args)
+    }
+}
+
+// 已注册的命令；help 由这里直接处理
+pub struct Commands<T> {
+    commands: Vec<Box<dyn Command<T>>>,
+}
+
+impl<T> Default for Commands<T> {
+    fn default() -> Self {
+        Self { commands: Vec::new() }
+    }
+}
+
+impl<T> Commands<T> {
+    pub fn register(&mut self, command: Box<dyn Command<T>>) {
+        self.commands.push(command);
+    }
+
+    // 执行一行命令，返回要显示在控制台中的输出
+    pub fn run(&self, target: &mut T, ctx: &mut Context, line: &str) -> String {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&name, args)) = words.split_first() else {
+            return String::new();
+        };
+        if name == "help" {
+            let mut lines = vec!["help".to_string(), "clear".to_string()];
+            lines.extend(self.commands.iter().map(|c| c.usage().to_string()));
+            return lines.join("\n");
+        }
+        match self.commands.iter().find(|c| c.name() == name) {
+            Some(command) => command.run(target, ctx, args).unwrap_or_else(|e| format!("error: {}", e)),
+            None => format!("unknown command: {} (try help)", name),
+        }
+    }
+}
+
+// 下拉式控制台（` 键开关）：输入行、输出记录与命令历史
+#[derive(Default)]
+pub struct Console {
+    pub open: bool,
+    input: String,
+    lines: Vec<String>,
+    history: Vec<String>,
+    // 上下键浏览历史时的位置
+    history_pos: Option<usize>,
+    // 下拉进度 0..1
+    slide: f32,
+}
+
+impl Console {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        let target = if self.open { 1.0 } else { 0.0 };
+        let step = dt / SLIDE_SECS;
+        self.slide = if self.slide < target { (self.slide + step).min(1.0) } else { (self.slide - step).max(0.0) };
+    }
+
+    pub fn type_char(&mut self, character: char) {
+        // 开关控制台的 ` 键本身不输入
+        if !character.is_control() && character != '`' {
+            self.input.push(character);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        self.input.pop();
+    }
+
+    // 上一条（older = true）/下一条历史命令
+    pub fn browse(&mut self, older: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+        let last = self.history.len() - 1;
+        self.history_pos = match (self.history_pos, older) {
+            (None, true) => Some(last),
+            (None, false) => None,
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) if i < last => Some(i + 1),
+            (Some(_), false) => None,
+        };
+        self.input = self.history_pos.map(|i| self.history[i].clone()).unwrap_or_default();
+    }
+
+    // 回车：取出输入行并记入历史；clear 在这里直接处理
+    pub fn submit(&mut self) -> Option<String> {
+        let line = std::mem::take(&mut self.input).trim().to_string();
+        self.history_pos = None;
+        if line.is_empty() {
+            return None;
+        }
+        if self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+        }
+        if line == "clear" {
+            self.lines.clear();
+            return None;
+        }
+        self.print(&format!("> {}", line));
+        Some(line)
+    }
+
+    pub fn print(&mut self, text: &str) {
+        self.lines.extend(text.lines().map(str::to_string));
+        let excess = self.lines.len().saturating_sub(MAX_LINES);
+        self.lines.drain(..excess);
+    }
+
+    // 画在所有界面与过渡遮罩之上
+    pub fn draw(&self, ctx: &mut Context, w: f32) -> GameResult {
+        if self.slide <= 0.0 {
+            return Ok(());
+        }
+        let h = (VISIBLE_LINES + 1) as f32 * LINE_H + 12.0;
+        let top = -h * (1.0 - self.slide);
+        let bg = graphics::Mesh::new_rectangle(ctx, DrawMode::fill(), Rect::new(0.0, top, w, h), Color::new(0.0, 0.0, 0.0, 0.8))?;
+        graphics::draw(ctx, &bg, DrawParam::default())?;
+        let start = self.lines.len().saturating_sub(VISIBLE_LINES);
+        for (i, line) in self.lines[start..].iter().enumerate() {
+            let text = graphics::Text::new((line.as_str(), graphics::Font::default(), 14.0));
+            graphics::draw(ctx, &text, DrawParam::default().dest([8.0, top + 4.0 + i as f32 * LINE_H]).color(Color::new(0.8, 0.8, 0.8, 1.0)))?;
+        }
+        let prompt = graphics::Text::new((format!("> {}_", self.input), graphics::Font::default(), 14.0));
+        graphics::draw(ctx, &prompt, DrawParam::default().dest([8.0, top + h - LINE_H - 4.0]).color(Color::YELLOW))
+    }
+}
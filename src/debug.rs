@@ -0,0 +1,120 @@
+use std::time::{Duration, Instant};
+
+use ggez::graphics::{self, Color, DrawMode, DrawParam, MeshBuilder, Rect};
+use ggez::{timer, Context, GameResult};
+
+use crate::world::{World, TILE_SIZE};
+
+// 速度向量的绘制长度（秒）：画出 0.1 秒内的位移
+const VELOCITY_SCALE: f32 = 0.1;
+
+// 调试浮层（F3 开关）：碰撞框、格子、速度、巡逻范围与帧时间
+#[derive(Default)]
+pub struct DebugOverlay {
+    pub enabled: bool,
+    // 本帧 update 开始的时刻与上一帧的 update/draw 用时
+    update_started: Option<Instant>,
+    update_time: Duration,
+    draw_time: Duration,
+}
+
+impl DebugOverlay {
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    pub fn begin_update(&mut self) {
+        self.update_started = Some(Instant::now());
+    }
+
+    // draw 开始时调用：update 到 draw 之间即为 update 用时
+    pub fn begin_draw(&mut self) -> Instant {
+        if let Some(started) = self.update_started.take() {
+            self.update_time = started.elapsed();
+        }
+        Instant::now()
+    }
+
+    pub fn end_draw(&mut self, started: Instant) {
+        self.draw_time = started.elapsed();
+    }
+
+    pub fn draw(&self, ctx: &mut Context, world: &World) -> GameResult {
+        let mut mb = MeshBuilder::new();
+        let thin = DrawMode::stroke(1.0);
+
+        // 格子
+        let grid = Color::new(1.0, 1.0, 1.0, 0.15);
+        let cols = (world.width / TILE_SIZE).ceil() as usize;
+        let rows = ((world.height - world.level_offset_y) / TILE_SIZE).ceil() as usize;
+        for col in 0..=cols {
+            let x = col as f32 * TILE_SIZE;
+            mb.line(&[[x, world.level_offset_y], [x, world.height]], 1.0, grid)?;
+        }
+        for row in 0..=rows {
+            let y = world.level_offset_y + row as f32 * TILE_SIZE;
+            mb.line(&[[0.0, y], [world.width, y]], 1.0, grid)?;
+        }
+
+        // 碰撞体：瓷砖灰、管道绿、金币黄、怪物红、玩家青
+        for tile in &world.tiles {
+            mb.rectangle(thin, *tile, Color::new(0.7, 0.7, 0.7, 0.8))?;
+        }
+        if let Some(pipe) = world.pipe_rect {
+            mb.rectangle(thin, pipe, Color::GREEN)?;
+        }
+        for (coin, _) in &world.coins {
+            mb.rectangle(thin, *coin, Color::YELLOW)?;
+        }
+        for m in &world.monsters {
+            mb.rectangle(thin, m.rect(), Color::RED)?;
+            // 巡逻范围：脚下的横线与两端竖线
+            let y = m.y + m.h + 2.0;
+            let range = Color::new(1.0, 0.5, 0.2, 1.0);
+            mb.line(&[[m.range_min, y], [m.range_max, y]], 1.0, range)?;
+            mb.line(&[[m.range_min, y - 6.0], [m.range_min, y + 6.0]], 1.0, range)?;
+            mb.line(&[[m.range_max, y - 6.0], [m.range_max, y + 6.0]], 1.0, range)?;
+        }
+        for p in &world.players {
+            let color = if p.on_ground { Color::CYAN } else { Color::MAGENTA };
+            mb.rectangle(thin, p.rect(), color)?;
+            let (cx, cy) = (p.x + p.w / 2.0, p.y + p.h / 2.0);
+            let (vx, vy) = (p.vx * VELOCITY_SCALE, p.vy * VELOCITY_SCALE);
+            if vx.abs() + vy.abs() > 0.5 {
+                mb.line(&[[cx, cy], [cx + vx, cy + vy]], 2.0, Color::WHITE)?;
+            }
+        }
+        let mesh = mb.build(ctx)?;
+        graphics::draw(ctx, &mesh, DrawParam::default())?;
+
+        // 格子坐标：上边标列号，左边标行号
+        let small = |text: String| graphics::Text::new((text, graphics::Font::default(), 10.0));
+        for col in 0..cols {
+            let text = small(col.to_string());
+            graphics::draw(ctx, &text, DrawParam::default().dest([col as f32 * TILE_SIZE + 2.0, world.level_offset_y + 2.0]).color(grid))?;
+        }
+        for row in 1..rows {
+            let text = small(row.to_string());
+            graphics::draw(ctx, &text, DrawParam::default().dest([2.0, world.level_offset_y + row as f32 * TILE_SIZE + 2.0]).color(grid))?;
+        }
+
+        // 玩家状态
+        for (slot, p) in world.players.iter().enumerate() {
+            let state = if p.on_ground { "ground" } else { "air" };
+            let text = small(format!("P{} {} v=({:.0},{:.0})", slot + 1, state, p.vx, p.vy));
+            graphics::draw(ctx, &text, DrawParam::default().dest([p.x, p.y - 12.0]))?;
+        }
+
+        // 帧率与用时（HUD 栏下方右侧）
+        let stats = format!(
+            "FPS {:.1}  update {:.2}ms  draw {:.2}ms",
+            timer::fps(ctx),
+            self.update_time.as_secs_f64() * 1000.0,
+            self.draw_time.as_secs_f64() * 1000.0
+        );
+        let text = graphics::Text::new((stats, graphics::Font::default(), 14.0));
+        let bg = graphics::Mesh::new_rectangle(ctx, DrawMode::fill(), Rect::new(world.width - 310.0, 50.0, 302.0, 20.0), Color::new(0.0, 0.0, 0.0, 0.6))?;
+        graphics::draw(ctx, &bg, DrawParam::default())?;
+        graphics::draw(ctx, &text, DrawParam::default().dest([world.width - 305.0, 53.0]))
+    }
+}
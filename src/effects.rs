@@ -0,0 +1,81 @@
+use ggez::graphics::{self, DrawParam};
+use ggez::{Context, GameResult};
+
+use crate::replay::FIXED_DT;
+
+// 飘字持续时间（秒）与上升速度（像素/秒）
+const POPUP_LIFE: f32 = 0.8;
+const POPUP_RISE: f32 = 40.0;
+// 闪屏持续时间（秒）
+const FLASH_SECS: f32 = 0.15;
+
+// 世界坐标中的飘字（+10、+100、1UP），上升并逐渐淡出
+struct Popup {
+    x: f32,
+    y: f32,
+    text: String,
+    color: graphics::Color,
+    age: f32,
+}
+
+// 打击反馈：飘字、顿帧（hit-stop）与闪屏；只随模拟步推进，暂停时一起冻结
+#[derive(Default)]
+pub struct Effects {
+    popups: Vec<Popup>,
+    // 剩余的顿帧步数，期间模拟不推进
+    hold_steps: u32,
+    flash: f32,
+}
+
+impl Effects {
+    pub fn popup(&mut self, x: f32, y: f32, text: String, color: graphics::Color) {
+        self.popups.push(Popup { x, y, text, color, age: 0.0 });
+    }
+
+    pub fn hit_stop(&mut self, steps: u32) {
+        self.hold_steps = self.hold_steps.max(steps);
+    }
+
+    pub fn flash(&mut self) {
+        self.flash = FLASH_SECS;
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    // 每个固定步调用一次；处于顿帧中时返回 true，调用方跳过这一步的模拟
+    pub fn step(&mut self) -> bool {
+        for p in &mut self.popups {
+            p.age += FIXED_DT;
+            p.y -= POPUP_RISE * FIXED_DT;
+        }
+        self.popups.retain(|p| p.age < POPUP_LIFE);
+        self.flash = (self.flash - FIXED_DT).max(0.0);
+        if self.hold_steps > 0 {
+            self.hold_steps -= 1;
+            return true;
+        }
+        false
+    }
+
+    pub fn draw_popups(&self, ctx: &mut Context) -> GameResult {
+        for p in &self.popups {
+            let mut color = p.color;
+            color.a = 1.0 - p.age / POPUP_LIFE;
+            let text = graphics::Text::new((p.text.as_str(), graphics::Font::default(), 16.0));
+            graphics::draw(ctx, &text, DrawParam::default().dest([p.x - 12.0, p.y]).color(color))?;
+        }
+        Ok(())
+    }
+
+    // 全屏白色闪光（画在 HUD 之前）
+    pub fn draw_flash(&self, ctx: &mut Context, w: f32, h: f32) -> GameResult {
+        if self.flash <= 0.0 {
+            return Ok(());
+        }
+        let alpha = self.flash / FLASH_SECS * 0.5;
+        let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), graphics::Rect::new(0.0, 0.0, w, h), graphics::Color::new(1.0, 1.0, 1.0, alpha))?;
+        graphics::draw(ctx, &mesh, DrawParam::default())
+    }
+}
@@ -0,0 +1,62 @@
+use std::fs;
+use std::path::Path;
+
+use ggez::{GameError, GameResult};
+use serde::{Deserialize, Serialize};
+
+use crate::replay::FIXED_DT;
+
+// 每隔几步记录一次位置，绘制时在相邻两点之间插值
+const SAMPLE_STEPS: u32 = 4;
+
+// 一次通关中 1 号玩家的轨迹，最快的一次作为“幽灵”在游戏中回放
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Ghost {
+    // 通关用时（秒）
+    pub time: f32,
+    samples: Vec<[f32; 2]>,
+    #[serde(skip)]
+    ticks: u32,
+}
+
+impl Ghost {
+    pub fn load(path: &Path) -> Option<Self> {
+        let text = fs::read_to_string(path).ok()?;
+        match serde_json::from_str(&text) {
+            Ok(ghost) => Some(ghost),
+            Err(e) => {
+                eprintln!("ignoring invalid ghost {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> GameResult {
+        let text = serde_json::to_string(self).map_err(|e| GameError::CustomError(e.to_string()))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    // 每个模拟步调用一次
+    pub fn record(&mut self, x: f32, y: f32) {
+        if self.ticks.is_multiple_of(SAMPLE_STEPS) {
+            self.samples.push([x, y]);
+        }
+        self.ticks += 1;
+    }
+
+    // 第 t 秒时的位置；轨迹结束后返回 None（幽灵已进入管道）
+    pub fn position(&self, t: f32) -> Option<[f32; 2]> {
+        let f = t / (SAMPLE_STEPS as f32 * FIXED_DT);
+        let i = f as usize;
+        let a = self.samples.get(i)?;
+        let Some(b) = self.samples.get(i + 1) else {
+            return Some(*a);
+        };
+        let k = f - i as f32;
+        Some([a[0] + (b[0] - a[0]) * k, a[1] + (b[1] - a[1]) * k])
+    }
+}
@@ -0,0 +1,348 @@
+// 供训练/评估机器人使用的 gym 风格接口：reset(seed, level) 与 step(action)。
+// 直接推进 World，不需要 Context，也不受帧率限制
+use crate::level::Level;
+use crate::replay::{self, FIXED_DT};
+use crate::save::{Power, START_LIVES};
+use crate::world::{Event, PlayerInput, World, MOVE_SPEED, TILE_SIZE, VIRTUAL_H, VIRTUAL_W};
+
+// 离散动作：移动、跳跃与跑步的所有组合（Fire 在模拟中还没有效果，不包含）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Idle,
+    Left,
+    Right,
+    Jump,
+    JumpLeft,
+    JumpRight,
+    RunLeft,
+    RunRight,
+    RunJumpLeft,
+    RunJumpRight,
+}
+
+impl Action {
+    pub const ALL: [Action; 10] = [
+        Action::Idle,
+        Action::Left,
+        Action::Right,
+        Action::Jump,
+        Action::JumpLeft,
+        Action::JumpRight,
+        Action::RunLeft,
+        Action::RunRight,
+        Action::RunJumpLeft,
+        Action::RunJumpRight,
+    ];
+}
+
+impl From<Action> for PlayerInput {
+    fn from(action: Action) -> Self {
+        let (move_x, jump, run) = match action {
+            Action::Idle => (0.0, false, false),
+            Action::Left => (-1.0, false, false),
+            Action::Right => (1.0, false, false),
+            Action::Jump => (0.0, true, false),
+            Action::JumpLeft => (-1.0, true, false),
+            Action::JumpRight => (1.0, true, false),
+            Action::RunLeft => (-1.0, false, true),
+            Action::RunRight => (1.0, false, true),
+            Action::RunJumpLeft => (-1.0, true, true),
+            Action::RunJumpRight => (1.0, true, true),
+        };
+        PlayerInput { move_x, jump, run, fire: false }
+    }
+}
+
+// 奖励权重
+#[derive(Clone, Copy, Debug)]
+pub struct Rewards {
+    // 每得 1 分
+    pub score: f32,
+    // 向管道每靠近 1 像素（远离为负）
+    pub progress: f32,
+    // 每失去一条命扣除
+    pub death: f32,
+    // 进入管道通关
+    pub victory: f32,
+    // 每个固定步扣除，鼓励尽快通关
+    pub step: f32,
+}
+
+impl Default for Rewards {
+    fn default() -> Self {
+        Self { score: 0.01, progress: 0.01, death: 1.0, victory: 1.0, step: 0.0 }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub rewards: Rewards,
+    // 观察范围：以玩家为中心向四周各看几格
+    pub view_radius: usize,
+    // 每次 step 重复同一动作的固定步数
+    pub frame_skip: u32,
+    // 一局最多的固定步数，到达后 done（None 为只受关卡限时约束）
+    pub max_steps: Option<u32>,
+    // 失去一条命即结束一局（否则直到生命用完）
+    pub end_on_death: bool,
+    // 开局按种子随机空跑 0..=n 步，让怪物巡逻的相位各不相同
+    pub random_start_steps: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { rewards: Rewards::default(), view_radius: 6, frame_skip: 1, max_steps: None, end_on_death: true, random_start_steps: 30 }
+    }
+}
+
+// 观察中的格子类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    Solid,
+    // 还没被顶开的特殊方块（同样是实心的）
+    Special,
+    Pipe,
+}
+
+#[derive(Clone, Debug)]
+pub struct Observation {
+    // 以玩家中心所在格为中心、边长 view_size 的格子，按行存放；关卡左右与下方之外视为实心
+    pub tiles: Vec<Cell>,
+    pub view_size: usize,
+    // 玩家左上角的世界坐标与速度
+    pub x: f32,
+    pub y: f32,
+    pub vx: f32,
+    pub vy: f32,
+    pub on_ground: bool,
+    // 相对玩家中心的位置（像素），由近到远：怪物为 [dx, dy, vx]，金币为 [dx, dy]
+    pub monsters: Vec<[f32; 3]>,
+    pub coins: Vec<[f32; 2]>,
+    // 管道顶部中点相对玩家中心的位置
+    pub pipe: Option<[f32; 2]>,
+    pub time_left: f32,
+    pub time_limit: f32,
+    pub lives: u32,
+    pub score: i32,
+}
+
+impl Observation {
+    // 定长的特征向量（供神经网络输入）：格子、玩家状态、最近 k 个怪物与金币、管道、剩余时间比例。
+    // 长度为 view_size² + 3 + 4k + 3k + 3 + 1；位置以格为单位，缺少的实体补 0
+    pub fn features(&self, k: usize) -> Vec<f32> {
+        let mut out = Vec::with_capacity(self.view_size * self.view_size + 7 * k + 7);
+        out.extend(self.tiles.iter().map(|cell| *cell as u8 as f32));
+        out.extend([self.vx / MOVE_SPEED, self.vy / MOVE_SPEED, self.on_ground as u8 as f32]);
+        for i in 0..k {
+            match self.monsters.get(i) {
+                Some([dx, dy, vx]) => out.extend([1.0, dx / TILE_SIZE, dy / TILE_SIZE, vx / MOVE_SPEED]),
+                None => out.extend([0.0; 4]),
+            }
+        }
+        for i in 0..k {
+            match self.coins.get(i) {
+                Some([dx, dy]) => out.extend([1.0, dx / TILE_SIZE, dy / TILE_SIZE]),
+                None => out.extend([0.0; 3]),
+            }
+        }
+        match self.pipe {
+            Some([dx, dy]) => out.extend([1.0, dx / TILE_SIZE, dy / TILE_SIZE]),
+            None => out.extend([0.0; 3]),
+        }
+        out.push(self.time_left / self.time_limit.max(1.0));
+        out
+    }
+}
+
+// 一次 step 的结果
+pub struct Step {
+    pub observation: Observation,
+    pub reward: f32,
+    pub done: bool,
+    // 这次 step 中发生的模拟事件（调试、统计用）
+    pub events: Vec<Event>,
+}
+
+pub struct Env {
+    pub config: Config,
+    world: World,
+    // 关卡中对齐到格子的实心方块（不会消失，只有特殊方块会变成普通方块）
+    solid: Vec<bool>,
+    cols: usize,
+    rows: usize,
+    steps: u32,
+    last_score: i32,
+    last_distance: f32,
+}
+
+impl Env {
+    pub fn new(config: Config, level: &Level) -> Self {
+        let mut env = Self {
+            config,
+            world: World::new(level, VIRTUAL_W, VIRTUAL_H, 1, START_LIVES, Power::Small),
+            solid: Vec::new(),
+            cols: 0,
+            rows: 0,
+            steps: 0,
+            last_score: 0,
+            last_distance: 0.0,
+        };
+        env.reset(0, level);
+        env
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    // 开始新的一局；同一种子与关卡得到完全相同的开局
+    pub fn reset(&mut self, seed: u64, level: &Level) -> Observation {
+        self.world = World::new(level, VIRTUAL_W, VIRTUAL_H, 1, START_LIVES, Power::Small);
+        self.cols = (VIRTUAL_W / TILE_SIZE).ceil() as usize;
+        self.rows = level.map.len();
+        self.solid = vec![false; self.cols * self.rows];
+        for t in &self.world.tiles {
+            // 管道顶部的薄碰撞条不对齐格子，由 pipe_rect 单独表示
+            if t.h < TILE_SIZE {
+                continue;
+            }
+            if let Some(i) = self.index(t.x + TILE_SIZE / 2.0, t.y + TILE_SIZE / 2.0) {
+                self.solid[i] = true;
+            }
+        }
+        // 简单的 64 位 LCG（与 particles 一样避免引入 rand 依赖）
+        let random = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+        let idle_steps = (random >> 33) % (self.config.random_start_steps as u64 + 1);
+        for _ in 0..idle_steps {
+            if self.world.outcome.is_some() {
+                break;
+            }
+            replay::step(&mut self.world, &[PlayerInput::default()]);
+        }
+        self.steps = 0;
+        self.last_score = self.world.score;
+        self.last_distance = self.pipe_distance();
+        self.observe()
+    }
+
+    pub fn step(&mut self, action: impl Into<PlayerInput>) -> Step {
+        let input = action.into();
+        let rewards = self.config.rewards;
+        let mut events = Vec::new();
+        let mut reward = 0.0;
+        for _ in 0..self.config.frame_skip.max(1) {
+            let step_events = replay::step(&mut self.world, &[input]);
+            let died = step_events.iter().any(|e| matches!(e, Event::Died | Event::GameOver));
+            events.extend(step_events);
+            self.steps += 1;
+            reward -= rewards.step;
+            if self.world.outcome.is_some() || (died && self.config.end_on_death) {
+                break;
+            }
+        }
+        let deaths = events.iter().filter(|e| matches!(e, Event::Died | Event::GameOver)).count();
+        reward -= deaths as f32 * rewards.death;
+        if events.contains(&Event::Victory) {
+            reward += rewards.victory;
+        }
+        reward += (self.world.score - self.last_score) as f32 * rewards.score;
+        self.last_score = self.world.score;
+        // 重生回到起点造成的距离变化不计入进度
+        let distance = self.pipe_distance();
+        if deaths == 0 {
+            reward += (self.last_distance - distance) * rewards.progress;
+        }
+        self.last_distance = distance;
+        let done = self.world.outcome.is_some()
+            || (deaths > 0 && self.config.end_on_death)
+            || self.config.max_steps.is_some_and(|max| self.steps >= max);
+        Step { observation: self.observe(), reward, done, events }
+    }
+
+    // 已经推进的固定步数（模拟时间 = steps * FIXED_DT）
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.steps as f32 * FIXED_DT
+    }
+
+    fn center(&self) -> (f32, f32) {
+        let p = &self.world.players[0];
+        (p.x + p.w / 2.0, p.y + p.h / 2.0)
+    }
+
+    // 玩家中心到管道顶部中点的距离
+    fn pipe_distance(&self) -> f32 {
+        let (cx, cy) = self.center();
+        self.world.pipe_rect.map_or(0.0, |pipe| (pipe.x + pipe.w / 2.0 - cx).hypot(pipe.y - cy))
+    }
+
+    fn index(&self, x: f32, y: f32) -> Option<usize> {
+        let col = (x / TILE_SIZE).floor();
+        let row = ((y - self.world.level_offset_y) / TILE_SIZE).floor();
+        if col < 0.0 || row < 0.0 || col as usize >= self.cols || row as usize >= self.rows {
+            return None;
+        }
+        Some(row as usize * self.cols + col as usize)
+    }
+
+    fn cell(&self, col: i64, row: i64) -> Cell {
+        if row < 0 {
+            return Cell::Empty;
+        }
+        if col < 0 || col as usize >= self.cols || row as usize >= self.rows {
+            return Cell::Solid;
+        }
+        let (col, row) = (col as usize, row as usize);
+        if self.world.special_blocks.contains(&(col, row)) {
+            return Cell::Special;
+        }
+        if let Some(pipe) = self.world.pipe_rect {
+            let x = (col as f32 + 0.5) * TILE_SIZE;
+            let y = self.world.level_offset_y + (row as f32 + 0.5) * TILE_SIZE;
+            if x >= pipe.x && x < pipe.x + pipe.w && y >= pipe.y && y < pipe.y + pipe.h {
+                return Cell::Pipe;
+            }
+        }
+        if self.solid[row * self.cols + col] { Cell::Solid } else { Cell::Empty }
+    }
+
+    pub fn observe(&self) -> Observation {
+        let (cx, cy) = self.center();
+        let radius = self.config.view_radius as i64;
+        let col = (cx / TILE_SIZE).floor() as i64;
+        let row = ((cy - self.world.level_offset_y) / TILE_SIZE).floor() as i64;
+        let mut tiles = Vec::new();
+        for r in row - radius..=row + radius {
+            for c in col - radius..=col + radius {
+                tiles.push(self.cell(c, r));
+            }
+        }
+        let distance = |d: &[f32]| d[0].hypot(d[1]);
+        let mut monsters: Vec<[f32; 3]> =
+            self.world.monsters.iter().map(|m| [m.x + m.w / 2.0 - cx, m.y + m.h / 2.0 - cy, m.vx]).collect();
+        monsters.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+        let mut coins: Vec<[f32; 2]> = self.world.coins.iter().map(|(c, _)| [c.x + c.w / 2.0 - cx, c.y + c.h / 2.0 - cy]).collect();
+        coins.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+        let p = &self.world.players[0];
+        Observation {
+            tiles,
+            view_size: self.config.view_radius * 2 + 1,
+            x: p.x,
+            y: p.y,
+            vx: p.vx,
+            vy: p.vy,
+            on_ground: p.on_ground,
+            monsters,
+            coins,
+            pipe: self.world.pipe_rect.map(|pipe| [pipe.x + pipe.w / 2.0 - cx, pipe.y - cy]),
+            time_left: self.world.time_left,
+            time_limit: self.world.time_limit,
+            lives: self.world.lives,
+            score: self.world.score,
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use ggez::{GameError, GameResult};
+use serde::{Deserialize, Serialize};
+
+// 每张排行榜保留的条目数
+pub const TABLE_LEN: usize = 10;
+// 名字最多字符数
+pub const NAME_LEN: usize = 12;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Entry {
+    pub name: String,
+    pub score: u32,
+}
+
+// 每个关卡一张排行榜，另有整局（战役）排行榜；均按分数从高到低排列。
+// campaign 为空时写成普通值 `campaign = []`，TOML 要求它出现在 levels 的各个表之前
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HighScores {
+    campaign: Vec<Entry>,
+    levels: BTreeMap<String, Vec<Entry>>,
+}
+
+impl HighScores {
+    // 文件缺失或损坏时从空表开始
+    pub fn load(path: &Path) -> Self {
+        let Ok(text) = fs::read_to_string(path) else {
+            return HighScores::default();
+        };
+        match toml::from_str(&text) {
+            Ok(scores) => scores,
+            Err(e) => {
+                eprintln!("ignoring invalid high score file {}: {}", path.display(), e);
+                HighScores::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> GameResult {
+        let text = toml::to_string(self).map_err(|e| GameError::ConfigError(e.to_string()))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    pub fn level(&self, level: &str) -> &[Entry] {
+        self.levels.get(level).map_or(&[], |t| t.as_slice())
+    }
+
+    pub fn campaign(&self) -> &[Entry] {
+        &self.campaign
+    }
+
+    pub fn level_qualifies(&self, level: &str, score: u32) -> bool {
+        qualifies(self.level(level), score)
+    }
+
+    pub fn campaign_qualifies(&self, score: u32) -> bool {
+        qualifies(&self.campaign, score)
+    }
+
+    // 返回名次（从 0 开始），未进榜时返回 None
+    pub fn add_level(&mut self, level: &str, entry: Entry) -> Option<usize> {
+        insert(self.levels.entry(level.to_string()).or_default(), entry)
+    }
+
+    pub fn add_campaign(&mut self, entry: Entry) -> Option<usize> {
+        insert(&mut self.campaign, entry)
+    }
+}
+
+fn qualifies(table: &[Entry], score: u32) -> bool {
+    score > 0 && (table.len() < TABLE_LEN || table.last().is_some_and(|e| score > e.score))
+}
+
+// 同分时先到者排前
+fn insert(table: &mut Vec<Entry>, entry: Entry) -> Option<usize> {
+    if !qualifies(table, entry.score) {
+        return None;
+    }
+    let rank = table.iter().position(|e| entry.score > e.score).unwrap_or(table.len());
+    table.insert(rank, entry);
+    table.truncate(TABLE_LEN);
+    Some(rank)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Entry, HighScores};
+
+    #[test]
+    fn level_scores_save_without_campaign_entries() {
+        let mut scores = HighScores::default();
+        scores.add_level("1-1", Entry { name: "AAA".to_string(), score: 500 });
+        let text = toml::to_string(&scores).expect("serializable");
+        let loaded: HighScores = toml::from_str(&text).unwrap();
+        assert_eq!(loaded.level("1-1")[0].score, 500);
+        assert!(loaded.campaign().is_empty());
+    }
+}
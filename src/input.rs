@@ -0,0 +1,405 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::hash::Hash;
+use std::path::Path;
+
+use ggez::event::{Axis, Button, GamepadId};
+use ggez::input::gamepad;
+use ggez::input::keyboard::{is_key_pressed, KeyCode};
+use ggez::{Context, GameError, GameResult};
+use serde::{Deserialize, Serialize};
+
+// 摇杆死区：偏移量小于该值时视为未推动
+const STICK_DEADZONE: f32 = 0.25;
+// 最多同时游玩的本地玩家数量
+pub const MAX_PLAYERS: usize = 4;
+
+// 游戏内的抽象动作，物理按键/手柄按钮通过 Bindings 映射到动作
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Run,
+    Pause,
+    Fire,
+}
+
+impl Action {
+    pub const ALL: [Action; 6] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Jump,
+        Action::Run,
+        Action::Pause,
+        Action::Fire,
+    ];
+
+    // 用于配置文件与选项界面显示
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "MoveLeft",
+            Action::MoveRight => "MoveRight",
+            Action::Jump => "Jump",
+            Action::Run => "Run",
+            Action::Pause => "Pause",
+            Action::Fire => "Fire",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.name() == name)
+    }
+}
+
+// 一帧内处于按下状态的动作集合（位掩码）
+#[derive(Clone, Copy, Default)]
+struct ActionSet(u8);
+
+impl ActionSet {
+    fn contains(self, action: Action) -> bool {
+        self.0 & (1 << action as u8) != 0
+    }
+
+    fn insert(&mut self, action: Action) {
+        self.0 |= 1 << action as u8;
+    }
+}
+
+// 可写入配置文件的按键名称（同时决定哪些按键允许被绑定）
+macro_rules! named {
+    ($ty:ident, $to_name:ident, $from_name:ident, [$($v:ident),* $(,)?]) => {
+        fn $to_name(v: $ty) -> Option<&'static str> {
+            match v {
+                $($ty::$v => Some(stringify!($v)),)*
+                _ => None,
+            }
+        }
+
+        fn $from_name(name: &str) -> Option<$ty> {
+            match name {
+                $(stringify!($v) => Some($ty::$v),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+named!(KeyCode, key_name, key_from_name, [
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9,
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    Left, Right, Up, Down, Space, Return, Escape, Tab, Back,
+    Insert, Delete, Home, End, PageUp, PageDown,
+    LShift, RShift, LControl, RControl, LAlt, RAlt,
+    Comma, Period, Slash, Semicolon, Apostrophe, LBracket, RBracket, Minus, Equals, Grave, Backslash,
+    Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+]);
+
+named!(Button, button_name, button_from_name, [
+    South, East, North, West, C, Z,
+    LeftTrigger, LeftTrigger2, RightTrigger, RightTrigger2,
+    Select, Start, Mode, LeftThumb, RightThumb,
+    DPadUp, DPadDown, DPadLeft, DPadRight,
+]);
+
+// 配置文件格式：动作名 -> 按键名列表
+#[derive(Default, Serialize, Deserialize)]
+struct BindingsFile {
+    #[serde(default)]
+    keys: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    buttons: BTreeMap<String, Vec<String>>,
+}
+
+// 动作到物理按键/手柄按钮的映射
+#[derive(Clone)]
+pub struct Bindings {
+    keys: HashMap<Action, Vec<KeyCode>>,
+    buttons: HashMap<Action, Vec<Button>>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        let keys = HashMap::from([
+            (Action::MoveLeft, vec![KeyCode::Left, KeyCode::A]),
+            (Action::MoveRight, vec![KeyCode::Right, KeyCode::D]),
+            (Action::Jump, vec![KeyCode::Space, KeyCode::W, KeyCode::Up]),
+            (Action::Run, vec![KeyCode::LShift]),
+            (Action::Pause, vec![KeyCode::Escape, KeyCode::P]),
+            (Action::Fire, vec![KeyCode::F]),
+        ]);
+        let buttons = HashMap::from([
+            (Action::MoveLeft, vec![Button::DPadLeft]),
+            (Action::MoveRight, vec![Button::DPadRight]),
+            (Action::Jump, vec![Button::South]),
+            (Action::Run, vec![Button::West]),
+            (Action::Pause, vec![Button::Start]),
+            (Action::Fire, vec![Button::East]),
+        ]);
+        Self { keys, buttons }
+    }
+}
+
+impl Bindings {
+    // 读取键位配置；文件缺失或损坏时使用默认键位，配置中未出现的动作也保留默认值
+    pub fn load(path: &Path) -> Self {
+        let mut bindings = Bindings::default();
+        let Ok(text) = fs::read_to_string(path) else {
+            return bindings;
+        };
+        let file: BindingsFile = match toml::from_str(&text) {
+            Ok(file) => file,
+            Err(e) => {
+                eprintln!("ignoring invalid bindings file {}: {}", path.display(), e);
+                return bindings;
+            }
+        };
+        // 无法识别的动作名与按键名给出警告后跳过
+        let unknown = |what: &str, name: &str| eprintln!("ignoring unknown {} {:?} in bindings file {}", what, name, path.display());
+        for (name, keys) in &file.keys {
+            let Some(action) = Action::from_name(name) else {
+                unknown("action", name);
+                continue;
+            };
+            let keys = keys.iter().filter_map(|k| key_from_name(k).or_else(|| {
+                unknown("key", k);
+                None
+            }));
+            bindings.keys.insert(action, keys.collect());
+        }
+        for (name, buttons) in &file.buttons {
+            let Some(action) = Action::from_name(name) else {
+                unknown("action", name);
+                continue;
+            };
+            let buttons = buttons.iter().filter_map(|b| button_from_name(b).or_else(|| {
+                unknown("button", b);
+                None
+            }));
+            bindings.buttons.insert(action, buttons.collect());
+        }
+        bindings
+    }
+
+    pub fn save(&self, path: &Path) -> GameResult {
+        let mut file = BindingsFile::default();
+        for action in Action::ALL {
+            let keys = self.keys(action).iter().filter_map(|k| key_name(*k)).map(String::from).collect();
+            file.keys.insert(action.name().to_string(), keys);
+            let buttons = self.buttons(action).iter().filter_map(|b| button_name(*b)).map(String::from).collect();
+            file.buttons.insert(action.name().to_string(), buttons);
+        }
+        let text = toml::to_string(&file).map_err(|e| GameError::ConfigError(e.to_string()))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn buttons(&self, action: Action) -> &[Button] {
+        self.buttons.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    // 把按键绑定为动作的第 index 个按键（index 超出时追加），该动作的其他按键保留，
+    // 并从其他动作上解除该按键以免冲突。无法写入配置文件的按键会被拒绝，返回 false
+    pub fn bind_key(&mut self, action: Action, index: usize, key: KeyCode) -> bool {
+        if key_name(key).is_none() {
+            return false;
+        }
+        bind(&mut self.keys, action, index, key);
+        true
+    }
+
+    pub fn bind_button(&mut self, action: Action, index: usize, button: Button) -> bool {
+        if button_name(button).is_none() {
+            return false;
+        }
+        bind(&mut self.buttons, action, index, button);
+        true
+    }
+
+    // 选项界面显示用，例如 "Space, W, Up | South"；selected 对应的按键与按钮加上方括号，
+    // 超出列表时显示为 "[+]"（追加）
+    pub fn describe(&self, action: Action, selected: Option<usize>) -> String {
+        fn list<T: Copy>(items: &[T], name: fn(T) -> Option<&'static str>, selected: Option<usize>) -> String {
+            let mut names: Vec<String> = items.iter().filter_map(|v| name(*v)).map(String::from).collect();
+            match selected {
+                Some(i) if i < names.len() => names[i] = format!("[{}]", names[i]),
+                Some(_) => names.push("[+]".to_string()),
+                None => {}
+            }
+            names.join(", ")
+        }
+        let keys = list(self.keys(action), key_name, selected);
+        let buttons = list(self.buttons(action), button_name, selected);
+        format!("{} | {}", keys, buttons)
+    }
+}
+
+// bind_key / bind_button 的共同部分
+fn bind<T: Copy + Eq + Hash>(map: &mut HashMap<Action, Vec<T>>, action: Action, index: usize, value: T) {
+    let mut values = map.remove(&action).unwrap_or_default();
+    for others in map.values_mut() {
+        others.retain(|v| *v != value);
+    }
+    match values.get_mut(index) {
+        Some(v) => *v = value,
+        None => values.push(value),
+    }
+    // 该动作原本在别的位置上已有这个按键时只保留一份
+    let mut seen = HashSet::new();
+    values.retain(|v| seen.insert(*v));
+    map.insert(action, values);
+}
+
+// 单个手柄的按钮与摇杆状态（由 gamepad 事件驱动）
+#[derive(Default)]
+struct PadState {
+    stick_x: f32,
+    dpad_x: f32,
+    buttons: HashSet<Button>,
+}
+
+// 输入管理：每帧把键盘与手柄状态折算成各玩家槽位的动作状态。
+// 槽位 0 属于键盘，每个已连接的手柄依次占用之后的一个槽位。
+pub struct Input {
+    pub bindings: Bindings,
+    slots: [Option<GamepadId>; MAX_PLAYERS],
+    pads: HashMap<GamepadId, PadState>,
+    current: [ActionSet; MAX_PLAYERS],
+    previous: [ActionSet; MAX_PLAYERS],
+    stick_x: [f32; MAX_PLAYERS],
+}
+
+impl Input {
+    pub fn new(bindings: Bindings) -> Self {
+        Self {
+            bindings,
+            slots: [None; MAX_PLAYERS],
+            pads: HashMap::new(),
+            current: [ActionSet::default(); MAX_PLAYERS],
+            previous: [ActionSet::default(); MAX_PLAYERS],
+            stick_x: [0.0; MAX_PLAYERS],
+        }
+    }
+
+    // 每帧开始时调用一次：处理手柄热插拔并刷新动作状态
+    pub fn update(&mut self, ctx: &Context) {
+        self.sync(ctx);
+        self.refresh(|key| is_key_pressed(ctx, key));
+    }
+
+    // 按当前的键盘（key_down 查询）与手柄状态进入新的一帧；测试中可直接传入按键状态
+    pub fn refresh(&mut self, key_down: impl Fn(KeyCode) -> bool) {
+        self.previous = self.current;
+        for slot in 0..MAX_PLAYERS {
+            let pad = self.slots[slot].and_then(|id| self.pads.get(&id));
+            let mut set = ActionSet::default();
+            for action in Action::ALL {
+                let key_down = slot == 0 && self.bindings.keys(action).iter().any(|k| key_down(*k));
+                let pad_down = pad.is_some_and(|p| self.bindings.buttons(action).iter().any(|b| p.buttons.contains(b)));
+                if key_down || pad_down {
+                    set.insert(action);
+                }
+            }
+            if let Some(pad) = pad {
+                // 部分手柄把十字键报告为轴
+                if pad.dpad_x < -0.5 {
+                    set.insert(Action::MoveLeft);
+                } else if pad.dpad_x > 0.5 {
+                    set.insert(Action::MoveRight);
+                }
+            }
+            self.current[slot] = set;
+            self.stick_x[slot] = pad.map_or(0.0, |p| p.stick_x);
+        }
+    }
+
+    // 检测手柄的插入与拔出，并相应地分配/释放槽位
+    fn sync(&mut self, ctx: &Context) {
+        let connected: Vec<GamepadId> = gamepad::gamepads(ctx).map(|(id, _)| id).collect();
+        for slot in self.slots.iter_mut() {
+            if let Some(id) = *slot
+                && !connected.contains(&id)
+            {
+                self.pads.remove(&id);
+                *slot = None;
+            }
+        }
+        for id in connected {
+            if self.slots.contains(&Some(id)) {
+                continue;
+            }
+            if let Some(free) = self.slots.iter_mut().skip(1).find(|s| s.is_none()) {
+                *free = Some(id);
+                self.pads.entry(id).or_default();
+            }
+        }
+    }
+
+    // 本地玩家数量：键盘始终占用槽位 0，其余按最高的手柄槽位计算
+    pub fn player_count(&self) -> usize {
+        self.slots.iter().rposition(|s| s.is_some()).map_or(1, |i| i + 1)
+    }
+
+    pub fn button(&mut self, btn: Button, id: GamepadId, down: bool) {
+        let Some(pad) = self.pads.get_mut(&id) else {
+            return;
+        };
+        if down {
+            pad.buttons.insert(btn);
+        } else {
+            pad.buttons.remove(&btn);
+        }
+    }
+
+    pub fn axis(&mut self, axis: Axis, value: f32, id: GamepadId) {
+        let Some(pad) = self.pads.get_mut(&id) else {
+            return;
+        };
+        match axis {
+            Axis::LeftStickX => pad.stick_x = apply_deadzone(value),
+            Axis::DPadX => pad.dpad_x = value,
+            _ => {}
+        }
+    }
+
+    pub fn pressed(&self, slot: usize, action: Action) -> bool {
+        self.current[slot].contains(action)
+    }
+
+    pub fn just_pressed(&self, slot: usize, action: Action) -> bool {
+        self.current[slot].contains(action) && !self.previous[slot].contains(action)
+    }
+
+    pub fn just_released(&self, slot: usize, action: Action) -> bool {
+        !self.current[slot].contains(action) && self.previous[slot].contains(action)
+    }
+
+    // 水平移动量：数字按键优先，否则使用摇杆的模拟量
+    pub fn move_x(&self, slot: usize) -> f32 {
+        let mut x = 0.0;
+        if self.pressed(slot, Action::MoveLeft) {
+            x -= 1.0;
+        }
+        if self.pressed(slot, Action::MoveRight) {
+            x += 1.0;
+        }
+        if x == 0.0 { self.stick_x[slot] } else { x }
+    }
+}
+
+// 去掉死区后重新映射到 [-1, 1]，避免越过死区时速度突变
+fn apply_deadzone(value: f32) -> f32 {
+    if value.abs() < STICK_DEADZONE {
+        0.0
+    } else {
+        (value - STICK_DEADZONE * value.signum()) / (1.0 - STICK_DEADZONE)
+    }
+}
@@ -0,0 +1,53 @@
+use std::fs;
+use std::path::Path;
+
+use ggez::{GameError, GameResult};
+use serde::{Deserialize, Serialize};
+
+// 关卡数据与元信息
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Level {
+    // 排行榜、存档与录像中使用的关卡标识
+    pub id: String,
+    pub name: String,
+    // 限时（秒），用完时失去一条命
+    pub time_limit: f32,
+    // 地图：'#' 是实心瓷砖，'P' 表示管道
+    pub map: Vec<String>,
+    // 特殊方块位置（格子坐标 col,row）
+    pub special_blocks: Vec<(usize, usize)>,
+    // 通关后解锁的下一关（resources/levels/<id>.toml），没有则为最后一关
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+impl Level {
+    // 读取任意路径下的关卡文件（命令行 --level）
+    pub fn load(path: &Path) -> GameResult<Self> {
+        let text = fs::read_to_string(path)?;
+        toml::from_str(&text).map_err(|e| GameError::CustomError(format!("invalid level file {}: {}", path.display(), e)))
+    }
+
+    // 内置的第一关
+    pub fn builtin() -> Self {
+        Self {
+            id: "1-1".to_string(),
+            name: "Grassland".to_string(),
+            time_limit: 100.0,
+            map: [
+                "............................",
+                "............................",
+                "............................",
+                "...........##...............",
+                "..................##........",
+                "......##....................",
+                "#######################P####",
+            ]
+            .iter()
+            .map(|row| row.to_string())
+            .collect(),
+            special_blocks: vec![(8, 2), (15, 2)],
+            next: None,
+        }
+    }
+}
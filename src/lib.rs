@@ -0,0 +1,9 @@
+// 游戏本体（main.rs）、图集打包工具（bin/atlas.rs）与集成测试（tests/）共用的部分：模拟本身不依赖 Context，输入映射只在每帧刷新时读取键盘
+pub mod atlas;
+pub mod gym;
+pub mod input;
+pub mod level;
+pub mod replay;
+pub mod save;
+pub mod scene;
+pub mod world;
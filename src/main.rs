@@ -1,562 +1,759 @@
-use ggez::event;
-use ggez::input::keyboard::{is_key_pressed, KeyCode};
+mod assets;
+mod audio;
+mod cli;
+mod commands;
+mod console;
+mod debug;
+mod effects;
+mod ghost;
+mod highscores;
+mod particles;
+mod settings;
+mod speedrun;
+
+use std::rc::Rc;
+
+use game::{atlas, input, level, replay, save, scene, world};
+use ggez::event::{self, Axis, Button, GamepadId, KeyCode, KeyMods};
+use ggez::filesystem;
 use ggez::input::mouse::MouseButton;
 use ggez::timer;
-use ggez::{Context, GameResult};
+use ggez::{Context, GameError, GameResult};
 use ggez::{graphics};
-// note: this file uses rand::Rng; add `rand = "0.8"` to Cargo.toml if missing
-use ggez::graphics::{DrawParam, Image};
-const COIN_SIZE: f32 = 16.0;
-
-const TILE_SIZE: f32 = 32.0;
-const GRAVITY: f32 = 1200.0;
-const MOVE_SPEED: f32 = 200.0;
-const JUMP_V: f32 = -420.0;
-
-// 关卡数据和特殊方块位置（格子坐标）
-const LEVEL: [&str; 7] = [
-    "............................",
-    "............................",
-    "............................",
-    "...........##...............",
-    "..................##........",
-    "......##....................",
-    "#######################P####",
+use ggez::graphics::DrawParam;
+use assets::{Assets, SpriteList};
+use audio::{Audio, Category, Music, Sfx};
+use cli::Args;
+use console::{Commands, Console};
+use debug::DebugOverlay;
+use effects::Effects;
+use ghost::Ghost;
+use highscores::{Entry, HighScores, NAME_LEN};
+use input::{Action, Bindings, Input, MAX_PLAYERS};
+use level::Level;
+use particles::Particles;
+use replay::{Replay, FIXED_DT, STEPS_PER_SEC};
+use save::{Power, SaveData, Slot, SLOT_COUNT, START_LIVES};
+use scene::{Scenes, Screen, Transition};
+use settings::{Language, Settings};
+use speedrun::Speedrun;
+use world::{rect_intersect, Event, Outcome, PlayerInput, World, COIN_SIZE, LOW_TIME, TILE_SIZE, TIME_BONUS, VIRTUAL_H, VIRTUAL_W};
+
+const GAME_ID: &str = "platformer";
+const AUTHOR: &str = "example";
+
+// resources/fonts/cjk.ttf 不存在时依次尝试的系统中文字体（Windows、macOS、常见 Linux 发行版）
+const SYSTEM_CJK_FONTS: [&str; 9] = [
+    "C:/Windows/Fonts/msyh.ttc",
+    "C:/Windows/Fonts/simhei.ttf",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "/System/Library/Fonts/STHeiti Light.ttc",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+    "/usr/share/fonts/wenquanyi/wqy-microhei/wqy-microhei.ttc",
 ];
 
-const SPECIAL_POSITIONS: &[(usize, usize)] = &[(8usize, 2usize), (15usize, 2usize)];
+// 选项界面左侧的设置项（自上而下），右侧为键位
+const SETTING_ROWS: [&str; 7] = ["Display", "Scale", "VSync", "Music", "SFX", "Language", "Ghost"];
 
-enum Screen {
-    Menu,
-    GameOver,
-    Victory,
-    Playing,
+// 暂停菜单的选项（自上而下）
+const PAUSE_ITEMS: [&str; 4] = ["Resume", "Restart Level", "Options", "Quit to Menu"];
+
+// 胜利界面上剩余时间折算为分数的速度（秒/秒）
+const TALLY_RATE: f32 = 60.0;
+
+// 各玩家的染色，用于区分多个本地玩家
+const PLAYER_TINTS: [graphics::Color; MAX_PLAYERS] = [
+    graphics::Color::new(1.0, 1.0, 1.0, 1.0),
+    graphics::Color::new(1.0, 0.6, 0.6, 1.0),
+    graphics::Color::new(0.6, 1.0, 0.6, 1.0),
+    graphics::Color::new(0.6, 0.7, 1.0, 1.0),
+];
+
+// 把虚拟画面等比缩放到窗口中央，多余部分留黑边
+fn fit_screen_coordinates(ctx: &mut Context) -> GameResult {
+    let (win_w, win_h) = graphics::drawable_size(ctx);
+    let scale = (win_w / VIRTUAL_W).min(win_h / VIRTUAL_H);
+    let (sw, sh) = (win_w / scale, win_h / scale);
+    graphics::set_screen_coordinates(ctx, graphics::Rect::new((VIRTUAL_W - sw) / 2.0, (VIRTUAL_H - sh) / 2.0, sw, sh))
 }
 
-struct Player {
-    x: f32,
-    y: f32,
-    w: f32,
-    h: f32,
-    vx: f32,
-    vy: f32,
-    on_ground: bool,
+// 窗口像素坐标 -> 虚拟画面坐标（用于鼠标点击）
+fn to_virtual(ctx: &Context, x: f32, y: f32) -> (f32, f32) {
+    let (win_w, win_h) = graphics::drawable_size(ctx);
+    let sc = graphics::screen_coordinates(ctx);
+    (sc.x + x / win_w * sc.w, sc.y + y / win_h * sc.h)
 }
 
-impl Player {
-    fn rect(&self) -> graphics::Rect {
-        graphics::Rect::new(self.x, self.y, self.w, self.h)
-    }
+// 选项界面第 i 行设置项 / 键位的位置（draw 与点击检测共用）
+fn setting_row_rect(i: usize) -> graphics::Rect {
+    graphics::Rect::new(20.0, 80.0 + i as f32 * 36.0, 370.0, 30.0)
 }
 
-struct GameState {
-    screen: Screen,
-    player: Player,
-    tiles: Vec<graphics::Rect>, // 平台块位置
-    tile_img: Image,
-    player_img: Image,
-    special_img: Image,
-    // special_blocks now stores grid positions (col,row)
-    special_blocks: Vec<(usize, usize)>,
-    // coins: rect + its grid position (col,row)
-    coins: Vec<(graphics::Rect, (usize, usize))>,
-    coin_img: Image,
-    score: i32,
-    coin_spawn_timer: f32,
-    coin_spawn_interval: f32,
-    // level vertical offset used to compute grid rows
-    level_offset_y: f32,
-    // positions where coin has been collected; won't respawn there
-    consumed_coin_positions: Vec<(usize, usize)>,
-    // monsters (enemies)
-    monsters: Vec<Monster>,
-    monster_img: Image,
-    // menu background
-    menu_img: Image,
-    // pipe (level goal)
-    pipe_img: Image,
-    pipe_rect: Option<graphics::Rect>,
-    // entering pipe state
-    entering_pipe: bool,
-    enter_timer: f32,
-    bg_img: Image,
+fn binding_row_rect(i: usize) -> graphics::Rect {
+    graphics::Rect::new(410.0, 80.0 + i as f32 * 36.0, 370.0, 30.0)
 }
 
-// 小怪兽结构体：带有巡逻范围
-struct Monster {
-    x: f32,
-    y: f32,
-    w: f32,
-    h: f32,
-    vx: f32,
-    range_min: f32,
-    range_max: f32,
+// 存档槽界面第 i 个槽位及其清除按钮
+fn slot_rect(i: usize) -> graphics::Rect {
+    graphics::Rect::new(VIRTUAL_W / 2.0 - 220.0, 90.0 + i as f32 * 70.0, 380.0, 56.0)
 }
 
-impl Monster {
-    fn rect(&self) -> graphics::Rect {
-        graphics::Rect::new(self.x, self.y, self.w, self.h)
-    }
+fn slot_clear_rect(i: usize) -> graphics::Rect {
+    graphics::Rect::new(VIRTUAL_W / 2.0 + 170.0, 90.0 + i as f32 * 70.0, 50.0, 56.0)
 }
 
-impl GameState {
-    // new 需要 Context 用来加载图片资源
-    fn new(ctx: &mut Context) -> GameResult<Self> {
-        let level = LEVEL;
-
-        let mut tiles = Vec::new();
-        // 使关卡底部对齐到窗口底部：计算整个关卡像素高度，然后从窗口高度减去它作为起始偏移
-        let (_win_w, win_h) = graphics::drawable_size(ctx);
-        let rows = level.len() as f32;
-        let level_px_h = rows * TILE_SIZE;
-        // 如果关卡高度比窗口高，offset_y 允许为负，从而保持原始布局
-        let offset_y = win_h - level_px_h;
-
-        // 解析地图：'#' 是实心瓷砖，'P' 表示管道（同时作为实心瓷砖）
-        let mut pipe_rect: Option<graphics::Rect> = None;
-        for (row, line) in level.iter().enumerate() {
-            for (col, ch) in line.chars().enumerate() {
-                if ch == '#' || ch == 'P' {
-                    let y = offset_y + (row as f32) * TILE_SIZE;
-                    let r = graphics::Rect::new(col as f32 * TILE_SIZE, y, TILE_SIZE, TILE_SIZE);
-                    tiles.push(r);
-                    if ch == 'P' {
-                        // 把管道放在该格子正上方
-                        let pipe_w = TILE_SIZE;
-                        let pipe_h = TILE_SIZE * 1.5;
-                        let px = col as f32 * TILE_SIZE + (TILE_SIZE - pipe_w) / 2.0;
-                        let py = y - pipe_h;
-                        pipe_rect = Some(graphics::Rect::new(px, py, pipe_w, pipe_h));
-                        // 为了让玩家可以站在管道顶部，添加一个薄的碰撞矩形
-                        let top_h = 8.0_f32;
-                        let top_rect = graphics::Rect::new(px, py, pipe_w, top_h);
-                        tiles.push(top_rect);
-                    }
-                }
-            }
-        }
+// 暂停菜单第 i 项按钮的位置（draw 与点击检测共用）
+fn pause_item_rect(w: f32, h: f32, i: usize) -> graphics::Rect {
+    graphics::Rect::new(w / 2.0 - 110.0, h / 2.0 - 70.0 + i as f32 * 44.0, 220.0, 36.0)
+}
 
-    // 加载资源（确保 resources/stock.png、player.png、special_block.png、coin.png、menu.png 存在）
-    let tile_img = Image::new(ctx, "/stock.png")?;
-    let player_img = Image::new(ctx, "/player.png")?;
-    let special_img = Image::new(ctx, "/special_block.png")?;
-    let coin_img = Image::new(ctx, "/coin.png")?;
-    // 怪物素材（resources/boast.png）
-    let monster_img = Image::new(ctx, "/boast.png")?;
-    // 菜单背景图
-    let menu_img = Image::new(ctx, "/menu_bg.png")?;
-    // 管道素材
-    let pipe_img = Image::new(ctx, "/pipe.png")?;
-    let bg_img = Image::new(ctx, "/bg.png")?;
-
-        let player = Player {
-            x: 50.0,
-            y: 50.0,
-            w: 24.0,
-            h: 30.0,
-            vx: 0.0,
-            vy: 0.0,
-            on_ground: false,
-        };
+// 胜利界面的按钮：MENU，以及还有下一关时的 NEXT（draw 与点击检测共用）
+fn victory_button_rect(i: usize, count: usize) -> graphics::Rect {
+    let (btn_w, gap) = (180.0, 20.0);
+    let x = VIRTUAL_W / 2.0 - (btn_w * count as f32 + gap * (count as f32 - 1.0)) / 2.0 + i as f32 * (btn_w + gap);
+    graphics::Rect::new(x, VIRTUAL_H * 0.6, btn_w, 44.0)
+}
 
-    // 在靠近地面的地方生成一个巡逻怪，范围放在地面的中间区域
-        let mut monsters = Vec::new();
-        let ground_tiles: Vec<&graphics::Rect> = tiles.iter().filter(|t| t.y >= win_h - TILE_SIZE - 1.0).collect();
-        if !ground_tiles.is_empty() {
-            let idx = ground_tiles.len() / 2;
-            let center_tile = ground_tiles[idx];
-            let center_x = center_tile.x + TILE_SIZE / 2.0;
-            let first_x = ground_tiles.first().unwrap().x;
-            let last_x = ground_tiles.last().unwrap().x + TILE_SIZE;
-            let mut range_min = center_x - TILE_SIZE * 3.0;
-            let mut range_max = center_x + TILE_SIZE * 3.0;
-            if range_min < first_x { range_min = first_x; }
-            if range_max > last_x { range_max = last_x; }
-            let mx = center_x - 12.0; // 居中放置怪物（宽 24）
-            let my = center_tile.y - 24.0;
-            monsters.push(Monster { x: mx, y: my, w: 24.0, h: 24.0, vx: 60.0, range_min, range_max });
-        }
-
-        // 管道位置由地图中 'P' 指定；若未指定，后续逻辑会尝试基于地面创建（见 draw/reset）
-
-    // 添加几个特殊方块（示例放在第 3 行和第 4 行的特定列）
-    let special_positions = SPECIAL_POSITIONS;
-    let mut special_blocks = Vec::new();
-    for (col, row) in special_positions.iter() {
-            let y = offset_y + (*row as f32) * TILE_SIZE;
-            let rect = graphics::Rect::new(*col as f32 * TILE_SIZE, y, TILE_SIZE, TILE_SIZE);
-            // 同时把它加入 tiles（保证为实心方块）
-            tiles.push(rect);
-            special_blocks.push((*col, *row));
-        }
+struct GameState {
+    // 界面栈（暂停菜单、选项等压在游戏之上）与界面间的过渡
+    scenes: Scenes,
+    // 当前关卡（地图、限时等元信息）
+    level: Level,
+    // 模拟状态（玩家、怪物、金币、计时器、分数……）
+    world: World,
+    input: Input,
+    // 选项界面中等待新按键的动作及其第几个按键/按钮（再次点击同一行切换到下一个）
+    rebinding: Option<(Action, usize)>,
+    bindings_path: std::path::PathBuf,
+    settings: Settings,
+    settings_path: std::path::PathBuf,
+    // 中文界面所需字体（resources/fonts/cjk.ttf 或系统字体），都没有时只能使用英文
+    cjk_font: Option<graphics::Font>,
+    audio: Audio,
+    high_scores: HighScores,
+    high_scores_path: std::path::PathBuf,
+    // 胜利/失败界面上正在输入的名字（分数进榜时才有）
+    name_entry: Option<String>,
+    // 存档槽（用户数据目录 saves/ 下）与本局使用的槽位
+    save_dir: std::path::PathBuf,
+    slots: Vec<Slot>,
+    current_slot: Option<usize>,
+    // 本次战役中已通过的各关分数之和（沿 Level::next 连续闯关）；回放或不在一局中时为 None
+    campaign: Option<i32>,
+    // 快速存档（F5 保存 / F9 读取）
+    quicksave_path: std::path::PathBuf,
+    // 本局的输入录像；一局结束时写入 replay_path
+    recording: Option<Replay>,
+    // 正在回放的录像及下一步的下标
+    playback: Option<(Replay, usize)>,
+    replay_path: std::path::PathBuf,
+    // 本关最快通关的轨迹（幽灵）与本局正在记录的轨迹
+    best_ghost: Option<Ghost>,
+    ghost_run: Option<Ghost>,
+    ghost_path: std::path::PathBuf,
+    // 速通计时与分段（用户数据目录 splits.json）
+    speedrun: Speedrun,
+    // 胜利界面上尚未计入显示分数的剩余时间（秒）
+    tally: f32,
+    // 飘字、顿帧与闪屏
+    effects: Effects,
+    particles: Particles,
+    // 图片与关卡文件（缺失时使用占位图，运行中热重载）
+    assets: Assets,
+    // F3 调试浮层
+    debug: DebugOverlay,
+    // ` 键开关的开发者控制台与已注册的命令（执行命令时需要同时借用 self）
+    console: Console,
+    commands: Rc<Commands<GameState>>,
+    // 慢动作倍率（1 为正常速度）与未用完的模拟步数
+    time_scale: f32,
+    step_budget: f32,
+}
+
+impl GameState {
+    // new 需要 Context 用来加载图片资源
+    fn new(ctx: &mut Context, settings: Settings, settings_path: std::path::PathBuf, resource_dir: std::path::PathBuf, audio_enabled: bool) -> GameResult<Self> {
+    // 键位配置保存在用户配置目录
+    let bindings_path = filesystem::user_config_dir(ctx).join("bindings.toml");
+    let bindings = Bindings::load(&bindings_path);
+    let cjk_font = load_cjk_font(ctx);
+    if settings.language == Language::Chinese && cjk_font.is_none() {
+        eprintln!("no CJK font found (add resources/fonts/cjk.ttf), falling back to English");
+    }
+    let audio = Audio::new(ctx, audio_enabled, settings.music_volume, settings.sfx_volume);
+    // 排行榜保存在用户数据目录
+    let high_scores_path = filesystem::user_data_dir(ctx).join("highscores.toml");
+    let high_scores = HighScores::load(&high_scores_path);
+    let save_dir = filesystem::user_data_dir(ctx).join("saves");
+    let slots = (0..SLOT_COUNT).map(|i| save::load_slot(&save::slot_path(&save_dir, i))).collect();
+    let quicksave_path = filesystem::user_data_dir(ctx).join("quicksave.json");
+    let replay_path = filesystem::user_data_dir(ctx).join("replays").join("last.json");
+    // 加载图片与关卡（resources/levels/<id>.toml，不存在时使用内置关卡）
+    let mut assets = Assets::new(ctx, resource_dir)?;
+    let level = assets.level(ctx, &Level::builtin().id);
+    let ghost_path = filesystem::user_data_dir(ctx).join("ghosts").join(format!("{}.json", level.id));
+    let best_ghost = Ghost::load(&ghost_path);
+    let speedrun = Speedrun::load(filesystem::user_data_dir(ctx).join("splits.json"));
+    let mut commands = Commands::default();
+    commands::register(&mut commands);
 
         Ok(Self {
-            screen: Screen::Menu,
-            player,
-            tiles,
-            tile_img,
-            player_img,
-            special_blocks,
-            special_img,
-            coins: Vec::new(),
-            coin_img,
-            score: 0,
-            coin_spawn_timer: 0.0,
-            coin_spawn_interval: 0.5,
-            level_offset_y: offset_y,
-            consumed_coin_positions: Vec::new(),
-            monsters,
-            monster_img,
-            menu_img,
-            pipe_img,
-            pipe_rect,
-            entering_pipe: false,
-            enter_timer: 0.0,
-            bg_img,
+            scenes: Scenes::new(Screen::Menu),
+            world: World::new(&level, VIRTUAL_W, VIRTUAL_H, 1, START_LIVES, Power::Small),
+            level,
+            input: Input::new(bindings),
+            rebinding: None,
+            bindings_path,
+            settings,
+            settings_path,
+            cjk_font,
+            audio,
+            high_scores,
+            high_scores_path,
+            name_entry: None,
+            save_dir,
+            slots,
+            current_slot: None,
+            campaign: None,
+            quicksave_path,
+            recording: None,
+            playback: None,
+            replay_path,
+            best_ghost,
+            ghost_run: None,
+            ghost_path,
+            speedrun,
+            tally: 0.0,
+            effects: Effects::default(),
+            particles: Particles::default(),
+            assets,
+            debug: DebugOverlay::default(),
+            console: Console::default(),
+            commands: Rc::new(commands),
+            time_scale: 1.0,
+            step_budget: 0.0,
         })
     }
 
-    // 重置一局（用于开始新游戏）
+    // 重置一局（用于开始新游戏），保留剩余生命与能力状态
     fn reset_game(&mut self) {
-        // 重新构建 tiles 与 special_blocks
-        self.tiles.clear();
-        let level = LEVEL;
-        // 解析地图以重建 tiles（同时处理 'P' 管道）
-        self.pipe_rect = None;
-        for (row, line) in level.iter().enumerate() {
-            for (col, ch) in line.chars().enumerate() {
-                if ch == '#' || ch == 'P' {
-                    let y = self.level_offset_y + (row as f32) * TILE_SIZE;
-                    let r = graphics::Rect::new(col as f32 * TILE_SIZE, y, TILE_SIZE, TILE_SIZE);
-                    self.tiles.push(r);
-                    if ch == 'P' {
-                        let pipe_w = TILE_SIZE;
-                        let pipe_h = TILE_SIZE * 1.5;
-                        let px = col as f32 * TILE_SIZE + (TILE_SIZE - pipe_w) / 2.0;
-                        let py = y - pipe_h;
-                        self.pipe_rect = Some(graphics::Rect::new(px, py, pipe_w, pipe_h));
-                        // 同样为 pipe 添加顶部薄的碰撞矩形，便于玩家站在管道上
-                        let top_h = 8.0_f32;
-                        let top_rect = graphics::Rect::new(px, py, pipe_w, top_h);
-                        self.tiles.push(top_rect);
-                    }
-                }
+        self.effects.clear();
+        self.particles.clear();
+        let count = self.input.player_count();
+        self.world = World::new(&self.level, VIRTUAL_W, VIRTUAL_H, count, self.world.lives, self.world.power);
+        self.playback = None;
+        self.recording = Some(Replay::new(&self.level.id, &self.world));
+        self.ghost_run = Some(Ghost::default());
+    }
+
+    // 切换关卡（命令行 --level、控制台 level 命令）：幽灵轨迹按关卡分别保存，随之重新读取
+    fn set_level(&mut self, ctx: &Context, level: Level) {
+        self.ghost_path = filesystem::user_data_dir(ctx).join("ghosts").join(format!("{}.json", level.id));
+        self.best_ghost = Ghost::load(&self.ghost_path);
+        self.level = level;
+        self.reset_game();
+    }
+
+    // 回放录像（菜单中回放最近一局，或命令行 --replay 指定的文件）
+    fn start_playback(&mut self, path: &std::path::Path) {
+        let replay = match Replay::load(path) {
+            Ok(replay) => replay,
+            Err(e) => {
+                eprintln!("cannot load replay {}: {}", path.display(), e);
+                return;
             }
+        };
+        if replay.level != self.level.id {
+            eprintln!("replay is for level {}, not {}", replay.level, self.level.id);
+            return;
         }
-        self.special_blocks.clear();
-        for (col, row) in SPECIAL_POSITIONS.iter() {
-            self.special_blocks.push((*col, *row));
-            // 同步把特殊方块的矩形也加入 tiles，保证可碰撞
-            let y = self.level_offset_y + (*row as f32) * TILE_SIZE;
-            let rect = graphics::Rect::new(*col as f32 * TILE_SIZE, y, TILE_SIZE, TILE_SIZE);
-            self.tiles.push(rect);
-        }
-        self.coins.clear();
-        self.consumed_coin_positions.clear();
-        self.score = 0;
-        self.coin_spawn_timer = 0.0;
-        self.player = Player { x:50.0, y:50.0, w:24.0, h:30.0, vx:0.0, vy:0.0, on_ground:false };
-        // reset pipe state and recompute pipe_rect based on current tiles
-        self.entering_pipe = false;
-        self.enter_timer = 0.0;
-        // If the map explicitly specified a pipe ('P'), keep it. Otherwise compute a fallback
-        if self.pipe_rect.is_none() {
-            // place pipe at rightmost ground tile (bottom row) as fallback
-            let ground_tiles: Vec<&graphics::Rect> = self.tiles.iter().filter(|t| t.y >= self.level_offset_y + (LEVEL.len() as f32 - 1.0) * TILE_SIZE - 1.0).collect();
-            if !ground_tiles.is_empty() {
-                let last = ground_tiles.last().unwrap();
-                let pipe_w = TILE_SIZE;
-                let pipe_h = TILE_SIZE * 1.5;
-                let px = last.x + (TILE_SIZE - pipe_w) / 2.0;
-                let py = last.y - pipe_h;
-                self.pipe_rect = Some(graphics::Rect::new(px, py, pipe_w, pipe_h));
-            }
-        }
-        // 重新生成怪物（放在地面中间范围）
-        self.monsters.clear();
-        let ground_tiles: Vec<&graphics::Rect> = self.tiles.iter().filter(|t| t.y >= self.level_offset_y + (LEVEL.len() as f32 - 1.0) * TILE_SIZE - 1.0).collect();
-        if !ground_tiles.is_empty() {
-            let idx = ground_tiles.len() / 2;
-            let center_tile = ground_tiles[idx];
-            let center_x = center_tile.x + TILE_SIZE / 2.0;
-            let first_x = ground_tiles.first().unwrap().x;
-            let last_x = ground_tiles.last().unwrap().x + TILE_SIZE;
-            let mut range_min = center_x - TILE_SIZE * 3.0;
-            let mut range_max = center_x + TILE_SIZE * 3.0;
-            if range_min < first_x { range_min = first_x; }
-            if range_max > last_x { range_max = last_x; }
-            let mx = center_x - 12.0;
-            let my = center_tile.y - 24.0;
-            self.monsters.push(Monster { x: mx, y: my, w: 24.0, h: 24.0, vx: 60.0, range_min, range_max });
-        }
-    }
-
-    // 重置玩家到初始状态（用于结束一把返回菜单）
+        self.effects.clear();
+        self.particles.clear();
+        self.world = World::new(&self.level, VIRTUAL_W, VIRTUAL_H, replay.players(), replay.lives, replay.power);
+        self.recording = None;
+        self.ghost_run = None;
+        self.campaign = None;
+        self.playback = Some((replay, 0));
+        self.scenes.reset(Screen::Playing, Transition::Fade);
+    }
+
+    // 通关时若比已有幽灵更快，则保存本局轨迹作为新的幽灵
+    fn save_ghost(&mut self) {
+        let Some(mut ghost) = self.ghost_run.take() else {
+            return;
+        };
+        ghost.time = self.world.level_time;
+        if self.best_ghost.as_ref().is_some_and(|best| best.time <= ghost.time) {
+            return;
+        }
+        if let Err(e) = ghost.save(&self.ghost_path) {
+            eprintln!("failed to save ghost: {}", e);
+        }
+        self.best_ghost = Some(ghost);
+    }
+
+    // 一局结束：保存录像（回放结束时不保存）
+    fn finish_recording(&mut self) {
+        self.playback = None;
+        let Some(mut replay) = self.recording.take() else {
+            return;
+        };
+        replay.finish(&self.world);
+        if let Err(e) = replay.save(&self.replay_path) {
+            eprintln!("failed to save replay: {}", e);
+        }
+    }
+
+    // 重置玩家到初始状态（结束一把回到菜单时，由 enter_screen 调用）
     fn reset_player(&mut self) {
-        self.player = Player {
-            x: 50.0,
-            y: 50.0,
-            w: 24.0,
-            h: 30.0,
-            vx: 0.0,
-            vy: 0.0,
-            on_ground: false,
+        self.speedrun.stop();
+        self.campaign = None;
+        self.world.set_player_count(self.input.player_count());
+        self.world.respawn();
+    }
+
+    // 实际使用的界面语言：缺少中文字体时回退到英文
+    fn language(&self) -> Language {
+        if self.cjk_font.is_some() { self.settings.language } else { Language::English }
+    }
+
+    fn ui_text(&self, text: &str, size: f32) -> graphics::Text {
+        let font = match (self.language(), self.cjk_font) {
+            (Language::Chinese, Some(font)) => font,
+            _ => graphics::Font::default(),
         };
-        // 失败重置时也把怪物位置重置为初始
-        for m in &mut self.monsters {
-            m.vx = m.vx.abs();
-            // 将怪物放回 range_min
-            m.x = m.range_min + 4.0;
+        graphics::Text::new((text, font, size))
+    }
+
+    // 按当前语言翻译界面文字
+    fn label(&self, text: &'static str, size: f32) -> graphics::Text {
+        self.ui_text(self.language().tr(text), size)
+    }
+
+    // 选项界面中设置项当前取值的显示文字
+    fn setting_value(&self, row: usize) -> String {
+        let lang = self.language();
+        match row {
+            0 => lang.tr(if self.settings.fullscreen { "Fullscreen" } else { "Windowed" }).to_string(),
+            1 => format!("{}x", self.settings.scale),
+            2 => lang.tr(if self.settings.vsync { "On (restart)" } else { "Off (restart)" }).to_string(),
+            3 => format!("{}%", (self.settings.music_volume * 100.0).round()),
+            4 => format!("{}%", (self.settings.sfx_volume * 100.0).round()),
+            // 选了中文但找不到字体时如实显示，而不是悄悄显示成英文
+            5 if self.cjk_font.is_none() && self.settings.language == Language::Chinese => "Chinese (no CJK font)".to_string(),
+            5 => lang.label().to_string(),
+            _ => lang.tr(if self.settings.ghost { "On" } else { "Off" }).to_string(),
         }
     }
 
-    // 简单 AABB 碰撞检测
-    fn rect_intersect(a: &graphics::Rect, b: &graphics::Rect) -> bool {
-        a.x < b.x + b.w && a.x + a.w > b.x && a.y < b.y + b.h && a.y + a.h > b.y
+    // 点击设置项：循环切换取值；显示模式与缩放立即应用，垂直同步需重启生效
+    fn change_setting(&mut self, ctx: &mut Context, row: usize) {
+        match row {
+            0 => self.settings.fullscreen = !self.settings.fullscreen,
+            1 => self.settings.next_scale(),
+            2 => self.settings.vsync = !self.settings.vsync,
+            3 => {
+                self.settings.music_volume = settings::next_volume(self.settings.music_volume);
+                self.audio.set_volume(Category::Music, self.settings.music_volume);
+            }
+            4 => {
+                self.settings.sfx_volume = settings::next_volume(self.settings.sfx_volume);
+                self.audio.set_volume(Category::Sfx, self.settings.sfx_volume);
+                self.audio.play(ctx, Sfx::Coin);
+            }
+            5 => {
+                self.settings.language = match self.settings.language {
+                    Language::English => Language::Chinese,
+                    Language::Chinese => Language::English,
+                };
+            }
+            _ => self.settings.ghost = !self.settings.ghost,
+        }
+        if row <= 1 {
+            let mode = self.settings.window_mode(VIRTUAL_W, VIRTUAL_H);
+            if let Err(e) = graphics::set_mode(ctx, mode).and_then(|_| fit_screen_coordinates(ctx)) {
+                eprintln!("failed to apply display settings: {}", e);
+            }
+        }
     }
-}
 
-impl event::EventHandler for GameState {
-    fn update(&mut self, ctx: &mut Context) -> GameResult {
-        match self.screen {
-            Screen::Menu => {
-                // 菜单无每帧逻辑（可加入动画）
+    // 从选中的存档槽开始一局：空槽位新建存档，读取剩余生命与能力状态，
+    // 从第一关沿 Level::next 走到最后一个已解锁的关卡
+    fn start_slot(&mut self, ctx: &mut Context, slot: usize) {
+        let mut level = self.assets.level(ctx, &Level::builtin().id);
+        if matches!(self.slots[slot], Slot::Empty) {
+            self.slots[slot] = Slot::Loaded(SaveData::new(&level.id));
+            self.write_slot(slot);
+        }
+        let Slot::Loaded(data) = &self.slots[slot] else {
+            return;
+        };
+        let (lives, power, unlocked) = (data.lives, data.power, data.unlocked_levels.clone());
+        // 关卡文件的 next 写成环时最多走遍已解锁的关卡
+        for _ in 0..unlocked.len() {
+            match level.next.clone() {
+                Some(next) if unlocked.contains(&next) => level = self.assets.level(ctx, &next),
+                _ => break,
             }
-            Screen::Victory => {
-                // Victory 屏幕暂停游戏逻辑
+        }
+        self.world.lives = lives;
+        self.world.power = power;
+        self.current_slot = Some(slot);
+        self.campaign = Some(0);
+        self.set_level(ctx, level);
+        self.scenes.reset(Screen::Playing, Transition::Iris);
+        self.speedrun.start(&self.level.id);
+    }
+
+    // 一局结束时更新当前存档：通关记录成绩；失败则下次从满生命重新开始
+    fn update_slot(&mut self, victory: bool) {
+        let Some(slot) = self.current_slot else {
+            return;
+        };
+        let Slot::Loaded(data) = &mut self.slots[slot] else {
+            return;
+        };
+        if victory {
+            data.record_level(&self.level.id, self.world.score.max(0) as u32, self.world.level_time);
+            if let Some(next) = &self.level.next {
+                data.unlock(next);
             }
-            Screen::GameOver => {
-                // 游戏结束时暂停一切游戏逻辑
+            // 已顶开的特殊方块计为找到的秘密
+            for block in self.level.special_blocks.iter().filter(|b| !self.world.special_blocks.contains(b)) {
+                data.record_secret(&self.level.id, *block);
             }
-            Screen::Playing => {
-                let dt = timer::delta(ctx).as_secs_f32();
-                let (_win_w, win_h) = graphics::drawable_size(ctx);
-
-                // 若正在进入管道，则播放缓慢滑入动画并在结束后切换到 Victory
-                if self.entering_pipe {
-                    if let Some(pipe) = self.pipe_rect {
-                        // 让玩家水平靠拢到管道中心并缓慢下滑
-                        let target_x = pipe.x + pipe.w / 2.0 - self.player.w / 2.0;
-                        // 平滑靠近
-                        let dx = target_x - self.player.x;
-                        self.player.x += dx * (0.1 + dt * 3.0);
-                        // 缓慢下滑
-                        self.player.y += 40.0 * dt;
-                        self.enter_timer += dt;
-                        if self.enter_timer > 1.2 || self.player.y > pipe.y + pipe.h * 0.5 {
-                            self.screen = Screen::Victory;
-                        }
-                        return Ok(());
-                    } else {
-                        self.entering_pipe = false;
-                    }
-                }
+            data.lives = self.world.lives;
+            data.power = self.world.power;
+        } else {
+            data.lives = START_LIVES;
+            data.power = Power::Small;
+        }
+        self.write_slot(slot);
+    }
 
-                // 输入
-                let mut move_x = 0.0;
-                if is_key_pressed(ctx, KeyCode::Left) || is_key_pressed(ctx, KeyCode::A) {
-                    move_x -= 1.0;
-                }
-                if is_key_pressed(ctx, KeyCode::Right) || is_key_pressed(ctx, KeyCode::D) {
-                    move_x += 1.0;
+    fn write_slot(&self, slot: usize) {
+        if let Slot::Loaded(data) = &self.slots[slot]
+            && let Err(e) = save::write_slot(&save::slot_path(&self.save_dir, slot), data)
+        {
+            eprintln!("failed to write save slot {}: {}", slot + 1, e);
+        }
+    }
+
+    // 存档槽界面中一个槽位的摘要
+    fn slot_summary(&self, slot: usize) -> String {
+        let lang = self.language();
+        let head = format!("{} {}", lang.tr("Slot"), slot + 1);
+        match &self.slots[slot] {
+            Slot::Empty => format!("{} - {}", head, lang.tr("Empty")),
+            Slot::Unreadable => format!("{} - {}", head, lang.tr("unreadable save")),
+            Slot::Loaded(data) => {
+                let mut text = format!("{} - {} {}", head, lang.tr("lives"), data.lives);
+                if let (Some(score), Some(time)) = (data.best_scores.get(&self.level.id), data.best_times.get(&self.level.id)) {
+                    text += &format!(", {} {}: {} ({:.1}s)", lang.tr("best"), self.level.id, score, time);
                 }
-                if (is_key_pressed(ctx, KeyCode::Space)
-                    || is_key_pressed(ctx, KeyCode::W)
-                    || is_key_pressed(ctx, KeyCode::Up))
-                    && self.player.on_ground
-                {
-                    self.player.vy = JUMP_V;
-                    self.player.on_ground = false;
+                if !self.level.special_blocks.is_empty() {
+                    text += &format!(", {} {}/{}", lang.tr("secrets"), data.secrets_in(&self.level.id), self.level.special_blocks.len());
                 }
+                text
+            }
+        }
+    }
 
-                // 水平速度
-                self.player.vx = move_x * MOVE_SPEED;
+    // 名字输入框（水平居中），没有进榜时不绘制
+    fn draw_name_entry(&self, ctx: &mut Context, y: f32) -> GameResult {
+        let Some(name) = &self.name_entry else {
+            return Ok(());
+        };
+        let rect = graphics::Rect::new(VIRTUAL_W / 2.0 - 230.0, y, 460.0, 30.0);
+        let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), rect, graphics::Color::from_rgba(0, 0, 0, 160))?;
+        graphics::draw(ctx, &mesh, DrawParam::default())?;
+        let lang = self.language();
+        let text = format!("{} {}: {}_  ({})", lang.tr("New high score!"), lang.tr("Name"), name, lang.tr("Enter to save"));
+        let label = self.ui_text(&text, 16.0);
+        graphics::draw(ctx, &label, DrawParam::default().dest([rect.x + 10.0, y + 7.0]))?;
+        Ok(())
+    }
 
-                // 应用重力
-                self.player.vy += GRAVITY * dt;
+    // 游戏中的 HUD：顶部一栏（分数、金币、关卡名、能力状态、倒计时、生命），下方为速通计时与回放标记
+    // 坐标均为虚拟分辨率，右上角留给 QUIT 按钮
+    fn draw_hud(&self, ctx: &mut Context) -> GameResult {
+        let lang = self.language();
+        let bar = graphics::Rect::new(8.0, 6.0, VIRTUAL_W - 112.0, 40.0);
+        let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), bar, graphics::Color::from_rgba(0, 0, 0, 110))?;
+        graphics::draw(ctx, &mesh, DrawParam::default())?;
 
-                // 先移动水平并检测水平碰撞
-                self.player.x += self.player.vx * dt;
-                let mut prect = self.player.rect();
-                for tile in &self.tiles {
-                    if GameState::rect_intersect(&prect, tile) {
-                        if self.player.vx > 0.0 {
-                            self.player.x = tile.x - self.player.w;
-                        } else if self.player.vx < 0.0 {
-                            self.player.x = tile.x + tile.w;
-                        }
-                        self.player.vx = 0.0;
-                        prect = self.player.rect();
-                    }
+        // 每栏：上方小标题，下方数值
+        let power = match self.world.power {
+            Power::Small => "Small",
+            Power::Big => "Big",
+            Power::Fire => "Fire",
+        };
+        let columns = [
+            ("SCORE", 20.0, format!("{:06}", self.world.score.max(0))),
+            ("COINS", 130.0, format!("x{:02}", self.world.coin_count)),
+            ("WORLD", 230.0, format!("{} {}", self.level.id, self.level.name)),
+            ("POWER", 400.0, lang.tr(power).to_string()),
+            ("TIME", 500.0, format!("{:03}", self.world.time_left.ceil() as i32)),
+            ("LIVES", 600.0, format!("x{}", self.world.lives)),
+        ];
+        // 时间不多时倒计时变红并闪烁
+        let low = self.world.time_left <= LOW_TIME;
+        for (title, x, value) in columns {
+            let label = self.label(title, 12.0);
+            graphics::draw(ctx, &label, DrawParam::default().dest([x, 9.0]).color(graphics::Color::from_rgb(255, 220, 120)))?;
+            let mut x = x;
+            if title == "COINS" {
+                let coin = self.assets.sprite("coin");
+                graphics::draw(ctx, coin.image(), coin.at(x, 26.0, 14.0, 14.0))?;
+                x += 18.0;
+            }
+            if title == "TIME" && low {
+                if (self.world.time_left * 4.0) as i32 % 2 == 0 {
+                    let text = self.ui_text(&value, 16.0);
+                    graphics::draw(ctx, &text, DrawParam::default().dest([x, 25.0]).color(graphics::Color::from_rgb(230, 70, 70)))?;
                 }
+                continue;
+            }
+            let text = self.ui_text(&value, 16.0);
+            graphics::draw(ctx, &text, DrawParam::default().dest([x, 25.0]))?;
+        }
 
-                // 然后移动垂直并检测垂直碰撞
-                self.player.y += self.player.vy * dt;
-                prect = self.player.rect();
-
-                // 地面随机刷新金币（周期性）
-                self.coin_spawn_timer += dt;
-                if self.coin_spawn_timer >= self.coin_spawn_interval {
-                    self.coin_spawn_timer = 0.0;
-                    // 找到底部的 tiles（y 接近窗口底部）
-                    let ground_tiles: Vec<&graphics::Rect> = self
-                        .tiles
-                        .iter()
-                        .filter(|t| t.y >= win_h - TILE_SIZE - 1.0)
-                        .collect();
-                    if !ground_tiles.is_empty() {
-                        // 选择中间的一个地面块刷金币，避免引入 rand 依赖
-                        let idx = ground_tiles.len() / 2;
-                        let t = ground_tiles[idx];
-                        let coin_x = t.x + (TILE_SIZE - COIN_SIZE) / 2.0;
-                        let coin_y = t.y - COIN_SIZE - 2.0;
-                        // 计算格子坐标
-                        let col = (t.x / TILE_SIZE) as usize;
-                        let row = ((t.y - self.level_offset_y) / TILE_SIZE) as usize;
-                        let exists = self.coins.iter().any(|(c, _)| (c.x - coin_x).abs() < 0.1 && (c.y - coin_y).abs() < 0.1);
-                        let consumed = self.consumed_coin_positions.iter().any(|(cc, rr)| *cc == col && *rr == row);
-                        if !exists && !consumed {
-                            self.coins.push((graphics::Rect::new(coin_x, coin_y, COIN_SIZE, COIN_SIZE), (col, row)));
-                        }
-                    }
-                }
-                self.player.on_ground = false;
-                for tile in &self.tiles {
-                    if GameState::rect_intersect(&prect, tile) {
-                        if self.player.vy > 0.0 {
-                            self.player.y = tile.y - self.player.h;
-                            self.player.vy = 0.0;
-                            self.player.on_ground = true;
-                        } else if self.player.vy < 0.0 {
-                            // 从下面顶到方块的处理：若是特殊方块，生成金币
-                            self.player.y = tile.y + tile.h;
-                            // 检查是否为特殊方块（比较格子坐标）
-                            let col = (tile.x / TILE_SIZE) as usize;
-                            let row = ((tile.y - self.level_offset_y) / TILE_SIZE) as usize;
-                            let is_special = self.special_blocks.iter().any(|(sc, sr)| *sc == col && *sr == row);
-                            if is_special {
-                                let coin_x = tile.x + (TILE_SIZE - COIN_SIZE) / 2.0;
-                                let coin_y = tile.y - COIN_SIZE - 2.0;
-                                // 只有当该位置没有金币且未被消耗时才生成
-                                let exists = self.coins.iter().any(|(c, _)| (c.x - coin_x).abs() < 0.1 && (c.y - coin_y).abs() < 0.1);
-                                let consumed = self.consumed_coin_positions.iter().any(|(cc, rr)| *cc == col && *rr == row);
-                                if !exists && !consumed {
-                                    self.coins.push((graphics::Rect::new(coin_x, coin_y, COIN_SIZE, COIN_SIZE), (col, row)));
-                                }
-                                // 把这个特殊方块变回普通瓷块（从 special_blocks 中移除）
-                                self.special_blocks.retain(|(sc, sr)| !(*sc == col && *sr == row));
-                            }
-                            self.player.vy = 0.0;
-                        }
-                        prect = self.player.rect();
-                    }
-                }
+        // 速通计时（HUD 栏下方，run= 以区别于关卡倒计时），与 PB 的差值领先为绿、落后为红
+        if self.speedrun.running() {
+            let text = self.ui_text(&format!("run={}", speedrun::format_time(self.speedrun.time())), 16.0);
+            graphics::draw(ctx, &text, DrawParam::default().dest([12.0, 52.0]))?;
+            if let Some(delta) = self.speedrun.delta(&self.level.id) {
+                let color = if delta <= 0.0 { graphics::Color::from_rgb(80, 220, 80) } else { graphics::Color::from_rgb(230, 70, 70) };
+                let sign = if delta <= 0.0 { "-" } else { "+" };
+                let text = self.ui_text(&format!("{}{}", sign, speedrun::format_time(delta.abs())), 16.0);
+                graphics::draw(ctx, &text, DrawParam::default().dest([124.0, 52.0]).color(color))?;
+            }
+        }
+        if self.playback.is_some() {
+            let label = self.label("REPLAY", 20.0);
+            graphics::draw(ctx, &label, DrawParam::default().dest([VIRTUAL_W / 2.0 - 36.0, 52.0]).color(graphics::Color::from_rgb(255, 80, 80)))?;
+        }
+        Ok(())
+    }
 
-                // 限制在窗口内（简单处理）
-                let (w, h) = graphics::drawable_size(ctx);
-                if self.player.x < 0.0 {
-                    self.player.x = 0.0;
-                }
-                if self.player.x + self.player.w > w {
-                    self.player.x = w - self.player.w;
-                }
-                if self.player.y + self.player.h > h {
-                    self.player.y = h - self.player.h;
-                    self.player.vy = 0.0;
-                    self.player.on_ground = true;
-                }
-
-                // 检测玩家是否真正站在管道顶部（水平中心在管道范围内且玩家底部与管道顶对齐）以触发进入管道
-                if let Some(pipe) = self.pipe_rect {
-                    let px_center = self.player.x + self.player.w / 2.0;
-                    if px_center >= pipe.x && px_center <= pipe.x + pipe.w {
-                        let player_bottom = self.player.y + self.player.h;
-                        // 仅在玩家底部与管道顶接近（容差）且被判定为着地时才触发进入
-                        let tol = 6.0_f32;
-                        if (player_bottom - pipe.y).abs() <= tol && self.player.on_ground {
-                            // 开始进入管道动画
-                            self.entering_pipe = true;
-                            self.enter_timer = 0.0;
-                            // 锁定横向移动
-                            self.player.vx = 0.0;
-                        }
-                    }
-                }
+    // 进入胜利/失败界面时调用：胜利记入本关与整局排行榜，失败只记入整局排行榜
+    fn begin_name_entry(&mut self, victory: bool) {
+        let score = self.world.score.max(0) as u32;
+        let level = victory && self.high_scores.level_qualifies(&self.level.id, score);
+        let campaign = self.campaign_over(victory) && self.high_scores.campaign_qualifies(self.campaign_total());
+        if level || campaign {
+            self.name_entry = Some(String::new());
+        }
+    }
 
-                // 固定帧率
-                while timer::check_update_time(ctx, 60) {
-                    // nothing
-                }
+    // 整局分数：本次战役已通过各关的分数加上当前关卡的分数
+    fn campaign_total(&self) -> u32 {
+        (self.campaign.unwrap_or(0) + self.world.score).max(0) as u32
+    }
 
-                // 拾取金币检测：玩家与金币相交则得分并移除金币
-                let pre_player = self.player.rect();
-                self.coins.retain(|(coin_rect, grid)| {
-                    if GameState::rect_intersect(&pre_player, coin_rect) {
-                        self.score += 10;
-                        // 触发下一周期立即刷新的机会：把计时器设为间隔
-                        self.coin_spawn_timer = self.coin_spawn_interval;
-                        // 记录该格子已被消耗，未来不再刷新
-                        self.consumed_coin_positions.push(*grid);
-                        false
-                    } else {
-                        true
-                    }
-                });
-
-                // 更新怪物巡逻与与玩家碰撞检测
-                for m in &mut self.monsters {
-                    // 移动
-                    m.x += m.vx * dt;
-                    if m.x < m.range_min {
-                        m.x = m.range_min;
-                        m.vx = m.vx.abs();
-                    } else if m.x + m.w > m.range_max {
-                        m.x = m.range_max - m.w;
-                        m.vx = -m.vx.abs();
-                    }
-                    // 简单重力作用 (保持在 tile 上方)
-                    // 检查是否站在某个 tile 上
-                    let mut on_tile = false;
-                    for tile in &self.tiles {
-                        let mut mrect = m.rect();
-                        mrect.y += 1.0; // 向下检测
-                        if GameState::rect_intersect(&mrect, tile) {
-                            on_tile = true;
-                            // 将怪物固定在地面上
-                            m.y = tile.y - m.h;
-                            break;
-                        }
-                    }
-                    if !on_tile {
-                        // 自由落体
-                        m.y += GRAVITY * dt;
-                    }
-                    // 玩家碰撞检测 -> 进入 GameOver
-                    if GameState::rect_intersect(&self.player.rect(), &m.rect()) {
-                        self.screen = Screen::GameOver;
+    // 战役在失败或通过最后一关时结束，这时整局分数才进入排行榜
+    fn campaign_over(&self, victory: bool) -> bool {
+        !victory || !self.has_next_level()
+    }
+
+    fn has_next_level(&self) -> bool {
+        self.campaign.is_some() && self.level.next.is_some()
+    }
+
+    // 胜利界面点击 NEXT：累计本关分数，带着剩余生命与能力状态进入下一关
+    fn next_level(&mut self, ctx: &mut Context) {
+        let (Some(score), Some(next)) = (self.campaign, self.level.next.clone()) else {
+            return;
+        };
+        // 进入下一关会重置分数，先提交名字
+        self.submit_score(true);
+        self.campaign = Some(score + self.world.score);
+        let level = self.assets.level(ctx, &next);
+        self.set_level(ctx, level);
+        self.scenes.reset(Screen::Playing, Transition::Iris);
+    }
+
+    // 离开胜利/失败界面前提交名字（未输入时记为 PLAYER）
+    fn submit_score(&mut self, victory: bool) {
+        let Some(name) = self.name_entry.take() else {
+            return;
+        };
+        let name = if name.trim().is_empty() { "PLAYER".to_string() } else { name.trim().to_string() };
+        let score = self.world.score.max(0) as u32;
+        if victory {
+            self.high_scores.add_level(&self.level.id, Entry { name: name.clone(), score });
+        }
+        if self.campaign_over(victory) {
+            self.high_scores.add_campaign(Entry { name, score: self.campaign_total() });
+        }
+        if let Err(e) = self.high_scores.save(&self.high_scores_path) {
+            eprintln!("failed to save high scores: {}", e);
+        }
+    }
+
+    // 把当前模拟状态快照写入快速存档文件
+    fn quick_save(&self) {
+        let result = self.world.snapshot().and_then(|text| {
+            if let Some(dir) = self.quicksave_path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(&self.quicksave_path, text)?;
+            Ok(())
+        });
+        if let Err(e) = result {
+            eprintln!("quick save failed: {}", e);
+        }
+    }
+
+    fn quick_load(&mut self) {
+        let result = std::fs::read_to_string(&self.quicksave_path).map_err(GameError::from).and_then(|text| World::restore(&text));
+        match result {
+            Ok(world) => {
+                self.world = world;
+                self.effects.clear();
+                self.particles.clear();
+                self.stop_tracking();
+                self.world.set_player_count(self.input.player_count());
+            }
+            Err(e) => eprintln!("quick load failed: {}", e),
+        }
+    }
+
+    // 读档或使用控制台作弊后的一局无法从头重放，停止录像、幽灵轨迹与速通计时
+    fn stop_tracking(&mut self) {
+        self.recording = None;
+        self.ghost_run = None;
+        self.speedrun.stop();
+    }
+
+    // 执行一行控制台命令并显示输出
+    fn run_command(&mut self, ctx: &mut Context, line: &str) {
+        let commands = Rc::clone(&self.commands);
+        let output = commands.run(self, ctx, line);
+        if !output.is_empty() {
+            self.console.print(&output);
+        }
+    }
+
+    // 模拟事件：播放音效，通关/失败时切换界面并记录成绩
+    fn handle_event(&mut self, ctx: &mut Context, event: Event) {
+        match event {
+            Event::Jump => self.audio.play(ctx, Sfx::Jump),
+            Event::Coin { x, y } => {
+                self.audio.play(ctx, Sfx::Coin);
+                self.particles.emit(&particles::COIN_SPARKLE, x, y);
+            }
+            Event::Bump => self.audio.play(ctx, Sfx::Bump),
+            Event::BlockOpened { x, y } => self.particles.emit(&particles::BLOCK_DEBRIS, x, y),
+            Event::Stomp { x, y } => {
+                self.audio.play(ctx, Sfx::Stomp);
+                self.effects.hit_stop(4);
+                self.particles.emit(&particles::MONSTER_BURST, x, y);
+            }
+            Event::Land { x, y } | Event::Skid { x, y } => self.particles.emit(&particles::DUST, x, y),
+            Event::Points { x, y, points } => {
+                let color = if points >= 100 { graphics::Color::from_rgb(255, 220, 120) } else { graphics::Color::WHITE };
+                self.effects.popup(x, y, format!("+{}", points), color);
+            }
+            Event::ExtraLife { x, y } => {
+                self.audio.play(ctx, Sfx::OneUp);
+                self.effects.popup(x, y, "1UP".to_string(), graphics::Color::from_rgb(80, 220, 80));
+                self.effects.flash();
+            }
+            Event::PipeEnter => {
+                self.audio.play(ctx, Sfx::PipeEnter);
+                if self.playback.is_none() {
+                    self.speedrun.split(&self.level.id);
+                }
+            }
+            Event::Died => {
+                self.audio.play(ctx, Sfx::Death);
+                self.effects.hit_stop(20);
+                self.effects.flash();
+            }
+            Event::TimeWarning => self.audio.play(ctx, Sfx::TimeWarning),
+            Event::Victory => {
+                self.scenes.finish(Outcome::Victory);
+                // 奖励分数已计入 world.score，界面上从剩余时间逐步累加显示
+                self.tally = self.world.time_left.max(0.0).ceil();
+                self.audio.play(ctx, Sfx::Victory);
+                // 回放不计入排行榜与存档
+                if self.playback.is_none() {
+                    self.begin_name_entry(true);
+                    self.update_slot(true);
+                    self.save_ghost();
+                    // 还有下一关时速通计时继续，通过最后一关才算跑完
+                    if self.campaign_over(true) {
+                        self.speedrun.finish();
                     }
                 }
+                self.finish_recording();
+            }
+            Event::GameOver => {
+                self.scenes.finish(Outcome::GameOver);
+                self.audio.play(ctx, Sfx::Death);
+                if self.playback.is_none() {
+                    self.begin_name_entry(false);
+                    self.update_slot(false);
+                    self.speedrun.stop();
+                }
+                self.finish_recording();
             }
         }
+    }
 
-        Ok(())
+    // 当前界面的背景音乐；胜利与失败界面只播放对应音效
+    fn music(&self) -> Option<Music> {
+        match self.scenes.top() {
+            Screen::Menu | Screen::Leaderboard | Screen::SlotSelect => Some(Music::Menu),
+            Screen::Options if !self.scenes.contains(Screen::Playing) => Some(Music::Menu),
+            Screen::Options | Screen::Playing | Screen::Pause => Some(Music::Level),
+            Screen::Victory | Screen::GameOver => None,
+        }
     }
 
-    fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        let bg = graphics::Color::from_rgb(100, 149, 237);
-        graphics::clear(ctx, bg);
+    // 界面进入/离开时的钩子（过渡到中点、界面栈变化时调用）
+    fn enter_screen(&mut self, screen: Screen) {
+        if screen == Screen::Menu {
+            // 一局结束回到菜单
+            self.reset_player();
+        }
+    }
+
+    fn exit_screen(&mut self, screen: Screen) {
+        match screen {
+            // 离开选项界面时保存设置与键位
+            Screen::Options => {
+                self.rebinding = None;
+                if let Err(e) = self.input.bindings.save(&self.bindings_path) {
+                    eprintln!("failed to save bindings: {}", e);
+                }
+                if let Err(e) = self.settings.save(&self.settings_path) {
+                    eprintln!("failed to save settings: {}", e);
+                }
+            }
+            // 离开胜利/失败界面前提交名字
+            Screen::Victory | Screen::GameOver => self.submit_score(screen == Screen::Victory),
+            _ => {}
+        }
+    }
 
-        match self.screen {
+    // 绘制一个界面（由 draw 按界面栈自下而上调用）
+    fn draw_screen(&mut self, ctx: &mut Context, screen: Screen) -> GameResult {
+        match screen {
             Screen::Menu => {
                 // 菜单背景和标题（居中、增加副标题和提示）
-                let (w, h) = graphics::drawable_size(ctx);
+                let (w, h) = (VIRTUAL_W, VIRTUAL_H);
 
                 // 绘制菜单背景图（铺满窗口，如果存在）
-                let iw = self.menu_img.width() as f32;
-                let ih = self.menu_img.height() as f32;
+                let iw = self.assets.image("menu").width() as f32;
+                let ih = self.assets.image("menu").height() as f32;
                 let sx = w / iw;
                 let sy = h / ih;
-                graphics::draw(ctx, &self.menu_img, DrawParam::default().dest([0.0, 0.0]).scale([sx, sy]))?;
+                graphics::draw(ctx, self.assets.image("menu"), DrawParam::default().dest([0.0, 0.0]).scale([sx, sy]))?;
 
                 // 标题文字
                 // let title = graphics::Text::new("Super Mario");
@@ -602,47 +799,177 @@ impl event::EventHandler for GameState {
                 graphics::draw(ctx, &mesh, DrawParam::default())?;
 
                 // 按钮文字（居中）
-                let label = graphics::Text::new("START");
+                let label = self.label("START", 16.0);
                 // 计算一个靠中文字目的偏移以近似居中
                 graphics::draw(ctx, &label, DrawParam::default().dest([bx + btn_w / 2.0 - 28.0, by + btn_h / 2.0 - 10.0]))?;
 
                 // 开始提示文字
-                let hint = graphics::Text::new("Click START to play");
+                let hint = self.label("Click START to play", 16.0);
                 graphics::draw(ctx, &hint, DrawParam::default().dest([w / 2.0 - 80.0, by + btn_h + 12.0]).color(graphics::Color::from_rgb(220, 220, 220)))?;
+
+                // Options、排行榜与回放按钮（位于提示文字下方，左右并排）
+                let row_w = 330.0;
+                let obtn_w = (row_w - 20.0) / 3.0;
+                let obtn_h = 40.0;
+                let oby = by + btn_h + 40.0;
+                let ox = w / 2.0 - row_w / 2.0;
+                let orect = graphics::Rect::new(ox, oby, obtn_w, obtn_h);
+                let omesh = graphics::Mesh::new_rectangle(
+                    ctx,
+                    graphics::DrawMode::fill(),
+                    orect,
+                    graphics::Color::from_rgb(70, 90, 140),
+                )?;
+                graphics::draw(ctx, &omesh, DrawParam::default())?;
+                let olabel = self.label("OPTIONS", 16.0);
+                graphics::draw(ctx, &olabel, DrawParam::default().dest([ox + obtn_w / 2.0 - 34.0, oby + obtn_h / 2.0 - 10.0]))?;
+                let sx = ox + obtn_w + 10.0;
+                let srect = graphics::Rect::new(sx, oby, obtn_w, obtn_h);
+                let smesh = graphics::Mesh::new_rectangle(
+                    ctx,
+                    graphics::DrawMode::fill(),
+                    srect,
+                    graphics::Color::from_rgb(140, 110, 40),
+                )?;
+                graphics::draw(ctx, &smesh, DrawParam::default())?;
+                let slabel = self.label("SCORES", 16.0);
+                graphics::draw(ctx, &slabel, DrawParam::default().dest([sx + obtn_w / 2.0 - 28.0, oby + obtn_h / 2.0 - 10.0]))?;
+                let rx = sx + obtn_w + 10.0;
+                let rrect = graphics::Rect::new(rx, oby, obtn_w, obtn_h);
+                let rmesh = graphics::Mesh::new_rectangle(
+                    ctx,
+                    graphics::DrawMode::fill(),
+                    rrect,
+                    graphics::Color::from_rgb(110, 70, 130),
+                )?;
+                graphics::draw(ctx, &rmesh, DrawParam::default())?;
+                let rlabel = self.label("REPLAY", 16.0);
+                graphics::draw(ctx, &rlabel, DrawParam::default().dest([rx + obtn_w / 2.0 - 28.0, oby + obtn_h / 2.0 - 10.0]))?;
+            }
+            Screen::SlotSelect => {
+                let (w, h) = (VIRTUAL_W, VIRTUAL_H);
+                let title = self.label("Select a save slot", 32.0);
+                graphics::draw(ctx, &title, DrawParam::default().dest([w / 2.0 - 150.0, 30.0]))?;
+
+                // 每个槽位一行，右侧为清除按钮
+                for i in 0..SLOT_COUNT {
+                    let row = slot_rect(i);
+                    let color = match self.slots[i] {
+                        Slot::Unreadable => graphics::Color::from_rgb(140, 60, 60),
+                        _ => graphics::Color::from_rgb(46, 125, 50),
+                    };
+                    let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), row, color)?;
+                    graphics::draw(ctx, &mesh, DrawParam::default())?;
+                    let label = self.ui_text(&self.slot_summary(i), 16.0);
+                    graphics::draw(ctx, &label, DrawParam::default().dest([row.x + 12.0, row.y + 20.0]))?;
+
+                    let clear = slot_clear_rect(i);
+                    let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), clear, graphics::Color::from_rgb(120, 120, 120))?;
+                    graphics::draw(ctx, &mesh, DrawParam::default())?;
+                    let label = self.ui_text("X", 20.0);
+                    graphics::draw(ctx, &label, DrawParam::default().dest([clear.x + 19.0, clear.y + 18.0]))?;
+                }
+
+                let back = graphics::Rect::new(w / 2.0 - 90.0, h - 60.0, 180.0, 40.0);
+                let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), back, graphics::Color::from_rgb(70, 90, 140))?;
+                graphics::draw(ctx, &mesh, DrawParam::default())?;
+                let label = self.label("BACK", 16.0);
+                graphics::draw(ctx, &label, DrawParam::default().dest([back.x + 70.0, back.y + 10.0]))?;
+            }
+            Screen::Leaderboard => {
+                let (w, h) = (VIRTUAL_W, VIRTUAL_H);
+                let title = self.label("High Scores", 32.0);
+                graphics::draw(ctx, &title, DrawParam::default().dest([w / 2.0 - 90.0, 20.0]))?;
+
+                // 左列：本关排行榜；右列：整局排行榜
+                let level_title = format!("{} {}", self.language().tr("Level"), self.level.id);
+                let columns = [
+                    (level_title, self.high_scores.level(&self.level.id), 60.0),
+                    (self.language().tr("Campaign").to_string(), self.high_scores.campaign(), w / 2.0 + 40.0),
+                ];
+                for (header, table, x) in columns {
+                    let header = self.ui_text(&header, 20.0);
+                    graphics::draw(ctx, &header, DrawParam::default().dest([x, 70.0]))?;
+                    for (i, entry) in table.iter().enumerate() {
+                        let row = self.ui_text(&format!("{:>2}. {:<12} {:>6}", i + 1, entry.name, entry.score), 16.0);
+                        graphics::draw(ctx, &row, DrawParam::default().dest([x, 100.0 + i as f32 * 22.0]))?;
+                    }
+                }
+
+                // BACK 按钮（与选项界面位置一致）
+                let btn_w = 180.0;
+                let btn_h = 40.0;
+                let by = h - 60.0;
+                let back = graphics::Rect::new(w / 2.0 - btn_w / 2.0, by, btn_w, btn_h);
+                let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), back, graphics::Color::from_rgb(46, 125, 50))?;
+                graphics::draw(ctx, &mesh, DrawParam::default())?;
+                let label = self.label("BACK", 16.0);
+                graphics::draw(ctx, &label, DrawParam::default().dest([back.x + btn_w / 2.0 - 20.0, by + 10.0]))?;
+            }
+            Screen::Options => {
+                let (w, h) = (VIRTUAL_W, VIRTUAL_H);
+                let title = self.label("Options", 36.0);
+                graphics::draw(ctx, &title, DrawParam::default().dest([w / 2.0 - 70.0, 20.0]))?;
+
+                // 左侧：设置项，点击循环切换取值
+                for (i, name) in SETTING_ROWS.iter().enumerate() {
+                    let row = setting_row_rect(i);
+                    let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), row, graphics::Color::from_rgba(0, 0, 0, 120))?;
+                    graphics::draw(ctx, &mesh, DrawParam::default())?;
+                    let text = format!("{}: {}", self.language().tr(name), self.setting_value(i));
+                    let label = self.ui_text(&text, 16.0);
+                    graphics::draw(ctx, &label, DrawParam::default().dest([row.x + 10.0, row.y + 7.0]))?;
+                }
+
+                // 右侧：每个动作一行，点击后等待新的按键或手柄按钮
+                for (i, action) in Action::ALL.iter().enumerate() {
+                    let row = binding_row_rect(i);
+                    let selected = self.rebinding.filter(|(a, _)| a == action).map(|(_, index)| index);
+                    let color = if selected.is_some() {
+                        graphics::Color::from_rgb(200, 160, 40)
+                    } else {
+                        graphics::Color::from_rgba(0, 0, 0, 120)
+                    };
+                    let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), row, color)?;
+                    graphics::draw(ctx, &mesh, DrawParam::default())?;
+                    let binding = self.input.bindings.describe(*action, selected);
+                    let label = self.ui_text(&format!("{:<10} {}", action.name(), binding), 16.0);
+                    graphics::draw(ctx, &label, DrawParam::default().dest([row.x + 10.0, row.y + 7.0]))?;
+                }
+                if self.rebinding.is_some() {
+                    let row = binding_row_rect(Action::ALL.len());
+                    let hint = self.ui_text(self.language().tr("press a key or button (click: next, Esc: cancel)"), 16.0);
+                    graphics::draw(ctx, &hint, DrawParam::default().dest([row.x + 10.0, row.y + 7.0]))?;
+                }
+
+                // BACK（保存并返回）与 RESET（恢复默认键位）按钮
+                let btn_w = 180.0;
+                let btn_h = 40.0;
+                let by = h - 60.0;
+                let back = graphics::Rect::new(w / 2.0 - btn_w - 10.0, by, btn_w, btn_h);
+                let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), back, graphics::Color::from_rgb(46, 125, 50))?;
+                graphics::draw(ctx, &mesh, DrawParam::default())?;
+                let label = self.label("BACK", 16.0);
+                graphics::draw(ctx, &label, DrawParam::default().dest([back.x + btn_w / 2.0 - 20.0, by + 10.0]))?;
+                let reset = graphics::Rect::new(w / 2.0 + 10.0, by, btn_w, btn_h);
+                let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), reset, graphics::Color::from_rgb(120, 120, 120))?;
+                graphics::draw(ctx, &mesh, DrawParam::default())?;
+                let label = self.label("RESET", 16.0);
+                graphics::draw(ctx, &label, DrawParam::default().dest([reset.x + btn_w / 2.0 - 24.0, by + 10.0]))?;
             }
             Screen::Playing => {
                 // 绘制背景
-                let (w, h) = graphics::drawable_size(ctx);
-                let iw = self.bg_img.width() as f32;
-                let ih = self.bg_img.height() as f32;
+                let (w, h) = (VIRTUAL_W, VIRTUAL_H);
+                let iw = self.assets.image("bg").width() as f32;
+                let ih = self.assets.image("bg").height() as f32;
                 let sx = w / iw;
                 let sy = h / ih;
-                graphics::draw(ctx, &self.bg_img, DrawParam::default().dest([0.0, 0.0]).scale([sx, sy]))?;
-
-                // 画瓷砖（使用图片，按 TILE_SIZE 缩放）
-                // 如果某个瓷砖与管道重叠，则不绘制该瓷砖（保留碰撞体），以便直接看到管道
-                for tile in &self.tiles {
-                    // 若是与管道相交则跳过绘制
-                    if let Some(pipe) = self.pipe_rect {
-                        if GameState::rect_intersect(tile, &pipe) {
-                            continue;
-                        }
-                    }
-                    let sx = TILE_SIZE / (self.tile_img.width() as f32);
-                    let sy = TILE_SIZE / (self.tile_img.height() as f32);
-                    graphics::draw(
-                        ctx,
-                        &self.tile_img,
-                        DrawParam::default()
-                            .dest([tile.x, tile.y])
-                            .scale([sx, sy]),
-                    )?;
-                }
+                graphics::draw(ctx, self.assets.image("bg"), DrawParam::default().dest([0.0, 0.0]).scale([sx, sy]))?;
 
                 //如果 pipe_rect 尚未生成（例如重置后），就在绘制阶段根据窗口和 tiles 计算它
-                if self.pipe_rect.is_none() {
-                    let (win_w, _win_h) = graphics::drawable_size(ctx);
-                    let ground_tiles: Vec<&graphics::Rect> = self.tiles.iter().filter(|t| t.y >= self.level_offset_y + (LEVEL.len() as f32 - 1.0) * TILE_SIZE - 1.0).collect();
+                if self.world.pipe_rect.is_none() {
+                    let win_w = VIRTUAL_W;
+                    let ground_tiles: Vec<&graphics::Rect> = self.world.tiles.iter().filter(|t| t.y >= self.world.level_offset_y + (self.level.map.len() as f32 - 1.0) * TILE_SIZE - 1.0).collect();
                     if !ground_tiles.is_empty() {
                         let chosen = ground_tiles.iter().rev().find(|t| t.x + TILE_SIZE <= win_w).or_else(|| ground_tiles.last());
                         if let Some(last) = chosen {
@@ -651,59 +978,72 @@ impl event::EventHandler for GameState {
                             // center above the chosen ground tile
                             let px = last.x + (TILE_SIZE - pipe_w) / 2.0;
                             let py = last.y - pipe_h;
-                            self.pipe_rect = Some(graphics::Rect::new(px, py, pipe_w, pipe_h));
+                            self.world.pipe_rect = Some(graphics::Rect::new(px, py, pipe_w, pipe_h));
                         }
                     }
                 }
 
+                // 关卡中的精灵按绘制顺序收集，同一图集的合并为一次绘制
+                let mut sprites = SpriteList::default();
+
+                // 画瓷砖（按 TILE_SIZE 缩放）
+                // 如果某个瓷砖与管道重叠，则不绘制该瓷砖（保留碰撞体），以便直接看到管道
+                let tile = self.assets.sprite("tile");
+                for t in &self.world.tiles {
+                    // 若是与管道相交则跳过绘制
+                    if let Some(pipe) = self.world.pipe_rect
+                        && rect_intersect(t, &pipe)
+                    {
+                        continue;
+                    }
+                    sprites.add(tile, tile.at(t.x, t.y, TILE_SIZE, TILE_SIZE));
+                }
+
                 // 绘制管道（若存在） — 放在玩家之前，以便玩家显示在管道前方
-                if let Some(pipe) = self.pipe_rect {
-                    let psx = pipe.w / (self.pipe_img.width() as f32);
-                    let psy = pipe.h / (self.pipe_img.height() as f32);
-                    graphics::draw(ctx, &self.pipe_img, DrawParam::default().dest([pipe.x, pipe.y]).scale([psx, psy]))?;
+                if let Some(pipe) = self.world.pipe_rect {
+                    let sprite = self.assets.sprite("pipe");
+                    sprites.add(sprite, sprite.at(pipe.x, pipe.y, pipe.w, pipe.h));
                 }
 
-                // 画玩家（使用图片，按 player.w/player.h 缩放）
-                let sx = self.player.w / (self.player_img.width() as f32);
-                let sy = self.player.h / (self.player_img.height() as f32);
-                graphics::draw(
-                    ctx,
-                    &self.player_img,
-                    DrawParam::default()
-                        .dest([self.player.x, self.player.y])
-                        .scale([sx, sy]),
-                )?;
+                // 幽灵：最快通关时 1 号玩家的位置，半透明绘制在玩家之后
+                let player_sprite = self.assets.sprite("player");
+                if self.settings.ghost
+                    && let Some(pos) = self.best_ghost.as_ref().and_then(|g| g.position(self.world.level_time))
+                    && let Some(player) = self.world.players.first()
+                {
+                    let param = player_sprite.at(pos[0], pos[1], player.w, player.h).color(graphics::Color::new(1.0, 1.0, 1.0, 0.35));
+                    sprites.add(player_sprite, param);
+                }
+
+                // 画玩家（按 player.w/player.h 缩放，按槽位染色）
+                for (slot, player) in self.world.players.iter().enumerate() {
+                    let param = player_sprite.at(player.x, player.y, player.w, player.h).color(PLAYER_TINTS[slot % MAX_PLAYERS]);
+                    sprites.add(player_sprite, param);
+                }
 
                 // 画特殊方块（special_blocks 存储格子坐标）
-                for (col, row) in &self.special_blocks {
+                let special = self.assets.sprite("special");
+                for (col, row) in &self.world.special_blocks {
                     let bx = (*col as f32) * TILE_SIZE;
-                    let by = self.level_offset_y + (*row as f32) * TILE_SIZE;
-                    let sx = TILE_SIZE / (self.special_img.width() as f32);
-                    let sy = TILE_SIZE / (self.special_img.height() as f32);
-                    graphics::draw(
-                        ctx,
-                        &self.special_img,
-                        DrawParam::default().dest([bx, by]).scale([sx, sy]),
-                    )?;
+                    let by = self.world.level_offset_y + (*row as f32) * TILE_SIZE;
+                    sprites.add(special, special.at(bx, by, TILE_SIZE, TILE_SIZE));
                 }
 
                 // 画金币
-                for (coin_rect, _) in &self.coins {
-                    let sx = COIN_SIZE / (self.coin_img.width() as f32);
-                    let sy = COIN_SIZE / (self.coin_img.height() as f32);
-                    graphics::draw(
-                        ctx,
-                        &self.coin_img,
-                        DrawParam::default().dest([coin_rect.x, coin_rect.y]).scale([sx, sy]),
-                    )?;
+                let coin = self.assets.sprite("coin");
+                for (coin_rect, _) in &self.world.coins {
+                    sprites.add(coin, coin.at(coin_rect.x, coin_rect.y, COIN_SIZE, COIN_SIZE));
                 }
 
-                // HUD 文本：仅显示分数
-                let text = graphics::Text::new(format!("score={}", self.score));
-                graphics::draw(ctx, &text, DrawParam::default().dest([8.0, 8.0]))?;
+                // 绘制怪物
+                let monster = self.assets.sprite("monster");
+                for m in &self.world.monsters {
+                    sprites.add(monster, monster.at(m.x, m.y, m.w, m.h));
+                }
+                sprites.draw(ctx)?;
 
                 // 退出按钮（右上）——现在为“结束当前一把并返回菜单”
-                let (w, _) = graphics::drawable_size(ctx);
+                let (w, _) = (VIRTUAL_W, VIRTUAL_H);
                 let btn_w = 80.0;
                 let btn_h = 28.0;
                 let bx = w - btn_w - 8.0;
@@ -716,44 +1056,63 @@ impl event::EventHandler for GameState {
                     graphics::Color::from_rgb(200, 80, 80),
                 )?;
                 graphics::draw(ctx, &mesh, DrawParam::default())?;
-                let label = graphics::Text::new("QUIT");
+                let label = self.label("QUIT", 16.0);
                 graphics::draw(ctx, &label, DrawParam::default().dest([bx + 18.0, by + 6.0]))?;
 
-                // 绘制怪物
-                for m in &self.monsters {
-                    let sx = m.w / (self.monster_img.width() as f32);
-                    let sy = m.h / (self.monster_img.height() as f32);
-                    graphics::draw(ctx, &self.monster_img, DrawParam::default().dest([m.x, m.y]).scale([sx, sy]))?;
+                self.particles.draw(ctx)?;
+                self.effects.draw_popups(ctx)?;
+                self.effects.draw_flash(ctx, VIRTUAL_W, VIRTUAL_H)?;
+                self.draw_hud(ctx)?;
+                if self.debug.enabled {
+                    self.debug.draw(ctx, &self.world)?;
+                }
+            }
+            Screen::Pause => {
+                // 暂停菜单：压暗下层的游戏画面并列出选项
+                let (w, h) = (VIRTUAL_W, VIRTUAL_H);
+                let overlay = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), graphics::Rect::new(0.0, 0.0, w, h), graphics::Color::from_rgba(0, 0, 0, 150))?;
+                graphics::draw(ctx, &overlay, DrawParam::default())?;
+                let title = self.label("Paused", 40.0);
+                graphics::draw(ctx, &title, DrawParam::default().dest([w / 2.0 - 60.0, h / 2.0 - 130.0]))?;
+                for (i, item) in PAUSE_ITEMS.iter().enumerate() {
+                    let rect = pause_item_rect(w, h, i);
+                    let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), rect, graphics::Color::from_rgb(70, 90, 140))?;
+                    graphics::draw(ctx, &mesh, DrawParam::default())?;
+                    let label = self.label(item, 16.0);
+                    graphics::draw(ctx, &label, DrawParam::default().dest([rect.x + 16.0, rect.y + 9.0]))?;
                 }
             }
             Screen::Victory => {
-                let (w, h) = graphics::drawable_size(ctx);
+                let (w, h) = (VIRTUAL_W, VIRTUAL_H);
                 // 半透明遮罩
                 let overlay = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), graphics::Rect::new(0.0, 0.0, w, h), graphics::Color::from_rgba(0, 0, 0, 140))?;
                 graphics::draw(ctx, &overlay, DrawParam::default())?;
 
                 // 胜利文字
-                let title = graphics::Text::new(("You Win!", graphics::Font::default(), 56.0));
+                let title = self.label("You Win!", 56.0);
                 graphics::draw(ctx, &title, DrawParam::default().dest([w / 2.0 - 120.0, h / 4.0]))?;
 
-                // 分数
-                let score_text = graphics::Text::new((format!("Score: {}", self.score), graphics::Font::default(), 28.0));
-                graphics::draw(ctx, &score_text, DrawParam::default().dest([w / 2.0 - 60.0, h / 2.6]))?;
+                // 分数与剩余时间：剩余时间逐秒折算为奖励分数
+                let lang = self.language();
+                let score = self.world.score - self.tally.ceil() as i32 * TIME_BONUS;
+                let text = format!("{}: {}   {}: {}", lang.tr("Score"), score, lang.tr("Time"), self.tally.ceil() as i32);
+                let score_text = self.ui_text(&text, 28.0);
+                graphics::draw(ctx, &score_text, DrawParam::default().dest([w / 2.0 - 150.0, h / 2.6]))?;
+                self.draw_name_entry(ctx, h / 2.6 + 40.0)?;
 
-                // 返回主菜单按钮
-                let btn_w = 180.0;
-                let btn_h = 44.0;
-                let bx = w / 2.0 - btn_w / 2.0;
-                let by = h * 0.6;
-                let rect = graphics::Rect::new(bx, by, btn_w, btn_h);
-                let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), rect, graphics::Color::from_rgb(46, 125, 50))?;
-                graphics::draw(ctx, &mesh, DrawParam::default())?;
-                let label = graphics::Text::new("MENU");
-                graphics::draw(ctx, &label, DrawParam::default().dest([bx + btn_w / 2.0 - 20.0, by + btn_h / 2.0 - 10.0]))?;
+                // 返回主菜单按钮；战役中还有下一关时另有 NEXT
+                let buttons: &[&'static str] = if self.has_next_level() { &["MENU", "NEXT"] } else { &["MENU"] };
+                for (i, name) in buttons.iter().enumerate() {
+                    let rect = victory_button_rect(i, buttons.len());
+                    let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), rect, graphics::Color::from_rgb(46, 125, 50))?;
+                    graphics::draw(ctx, &mesh, DrawParam::default())?;
+                    let label = self.label(name, 16.0);
+                    graphics::draw(ctx, &label, DrawParam::default().dest([rect.x + rect.w / 2.0 - 20.0, rect.y + rect.h / 2.0 - 10.0]))?;
+                }
             }
             Screen::GameOver => {
-                let (w, h) = graphics::drawable_size(ctx);
-                let title = graphics::Text::new(("Game Over", graphics::Font::default(), 48.0));
+                let (w, h) = (VIRTUAL_W, VIRTUAL_H);
+                let title = self.label("Game Over", 48.0);
                 graphics::draw(ctx, &title, DrawParam::default().dest([w / 2.0 - 100.0, h / 4.0]))?;
 
                 // 两个按钮：Restart 和 Quit
@@ -764,7 +1123,7 @@ impl event::EventHandler for GameState {
                 let rect = graphics::Rect::new(bx, by, btn_w, btn_h);
                 let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), rect, graphics::Color::from_rgb(200, 80, 80))?;
                 graphics::draw(ctx, &mesh, DrawParam::default())?;
-                let label = graphics::Text::new("Restart");
+                let label = self.label("Restart", 16.0);
                 graphics::draw(ctx, &label, DrawParam::default().dest([bx + 36.0, by + 10.0]))?;
 
                 let bx2 = bx;
@@ -772,11 +1131,151 @@ impl event::EventHandler for GameState {
                 let rect2 = graphics::Rect::new(bx2, by2, btn_w, btn_h);
                 let mesh2 = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), rect2, graphics::Color::from_rgb(120, 120, 120))?;
                 graphics::draw(ctx, &mesh2, DrawParam::default())?;
-                let label2 = graphics::Text::new("Quit");
+                let label2 = self.label("Quit", 16.0);
                 graphics::draw(ctx, &label2, DrawParam::default().dest([bx2 + 56.0, by2 + 10.0]))?;
+
+                // 分数与进榜时的名字输入（按钮下方）
+                let score_text = self.ui_text(&format!("{}: {}", self.language().tr("Score"), self.world.score), 20.0);
+                graphics::draw(ctx, &score_text, DrawParam::default().dest([w / 2.0 - 50.0, by2 + btn_h + 12.0]))?;
+                self.draw_name_entry(ctx, by2 + btn_h + 40.0)?;
             }
         }
+        Ok(())
+    }
+}
 
+impl event::EventHandler for GameState {
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        self.debug.begin_update();
+        self.input.update(ctx);
+        // 本帧应推进的固定步数；非游戏界面或暂停时直接丢弃，避免恢复后连续补帧
+        let mut steps = 0;
+        while timer::check_update_time(ctx, STEPS_PER_SEC) {
+            steps += 1;
+        }
+        // 慢动作：按倍率累计，攒够一整步才推进
+        self.step_budget += steps as f32 * self.time_scale;
+        let steps = self.step_budget as u32;
+        self.step_budget -= steps as f32;
+        self.console.update(timer::delta(ctx).as_secs_f32());
+        // 推进界面过渡，到中点时调用离开/进入钩子
+        if let Some(switch) = self.scenes.update(timer::delta(ctx).as_secs_f32()) {
+            for screen in switch.exited {
+                self.exit_screen(screen);
+            }
+            if let Some(screen) = switch.entered {
+                self.enter_screen(screen);
+            }
+        }
+        // 资源热重载：关卡文件改动后，游戏中立即以新关卡重开（回放中不打断）
+        if let Some(level) = self.assets.poll(ctx, timer::delta(ctx).as_secs_f32(), &self.level.id) {
+            self.level = level;
+            if self.scenes.contains(Screen::Playing) && self.playback.is_none() {
+                self.reset_game();
+            }
+        }
+        let music = self.music();
+        self.audio.update(ctx, music);
+        // 过渡期间各界面都不推进
+        if self.scenes.busy() {
+            return Ok(());
+        }
+        match self.scenes.top() {
+            Screen::Menu => {
+                // 菜单无每帧逻辑（可加入动画）
+            }
+            Screen::Victory => {
+                // Victory 屏幕暂停游戏逻辑，只推进剩余时间的折算动画；战役还没结束时速通计时照走
+                let dt = timer::delta(ctx).as_secs_f32();
+                self.tally = (self.tally - TALLY_RATE * dt).max(0.0);
+                self.speedrun.tick(dt);
+            }
+            Screen::GameOver => {
+                // 游戏结束时暂停一切游戏逻辑
+            }
+            Screen::Options | Screen::Leaderboard | Screen::SlotSelect => {
+                // 选项、排行榜与存档槽界面由按键/点击事件驱动
+            }
+            Screen::Pause => {
+                // 暂停期间不推进任何逻辑与计时器（coin_spawn_timer、enter_timer 等），再按暂停键继续
+                if (0..MAX_PLAYERS).any(|slot| self.input.just_pressed(slot, Action::Pause)) {
+                    self.scenes.pop(Transition::Cut);
+                }
+            }
+            Screen::Playing => {
+                if !self.console.open && (0..MAX_PLAYERS).any(|slot| self.input.just_pressed(slot, Action::Pause)) {
+                    self.scenes.push(Screen::Pause, Transition::Cut);
+                    return Ok(());
+                }
+
+                // 以固定步长推进模拟，录像按步记录输入，回放时逐步重现
+                for _ in 0..steps {
+                    self.particles.update(FIXED_DT);
+                    // 速通计时只在暂停菜单中停表，顿帧期间照常计时
+                    if self.playback.is_none() {
+                        self.speedrun.tick(FIXED_DT);
+                    }
+                    // 顿帧期间跳过模拟（不读取输入、不录像），回放时同样跳过，保持一致
+                    if self.effects.step() {
+                        continue;
+                    }
+                    let inputs = match &mut self.playback {
+                        Some((replay, step)) => {
+                            let Some(inputs) = replay.steps.get(*step).cloned() else {
+                                // 录像播完仍未结束（不应发生），回到菜单
+                                self.playback = None;
+                                self.scenes.reset(Screen::Menu, Transition::Fade);
+                                break;
+                            };
+                            *step += 1;
+                            inputs
+                        }
+                        // 控制台打开时键盘用于输入命令，玩家视为无输入
+                        None if self.console.open => vec![PlayerInput::default(); self.input.player_count()],
+                        // 输入（键盘 + 手柄，经 Bindings 映射为动作）；玩家数随手柄热插拔变化
+                        None => (0..self.input.player_count())
+                            .map(|slot| PlayerInput {
+                                move_x: self.input.move_x(slot),
+                                jump: self.input.pressed(slot, Action::Jump),
+                                run: self.input.pressed(slot, Action::Run),
+                                fire: self.input.pressed(slot, Action::Fire),
+                            })
+                            .collect(),
+                    };
+                    if let Some(recording) = &mut self.recording {
+                        recording.steps.push(inputs.clone());
+                    }
+                    let events = replay::step(&mut self.world, &inputs);
+                    if let (Some(ghost), Some(player)) = (&mut self.ghost_run, self.world.players.first()) {
+                        ghost.record(player.x, player.y);
+                    }
+                    for event in events {
+                        self.handle_event(ctx, event);
+                    }
+                    if self.scenes.busy() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        let bg = graphics::Color::from_rgb(100, 149, 237);
+        let draw_started = self.debug.begin_draw();
+        graphics::clear(ctx, bg);
+
+        // 自下而上绘制可见的界面（浮层之下的界面也要画），最后盖上过渡遮罩
+        for screen in self.scenes.visible().to_vec() {
+            self.draw_screen(ctx, screen)?;
+        }
+        self.scenes.draw(ctx, VIRTUAL_W, VIRTUAL_H)?;
+        self.console.draw(ctx, VIRTUAL_W)?;
+
+        // present 会等待垂直同步，不计入绘制用时
+        self.debug.end_draw(draw_started);
         graphics::present(ctx)?;
         Ok(())
     }
@@ -789,78 +1288,471 @@ impl event::EventHandler for GameState {
         x: f32,
         y: f32,
     ) {
-        if button != MouseButton::Left {
+        // 过渡期间忽略点击
+        if button != MouseButton::Left || self.scenes.busy() {
             return;
         }
+        let (x, y) = to_virtual(ctx, x, y);
 
-        match self.screen {
+        match self.scenes.top() {
             Screen::Menu => {
-                let (w, h) = graphics::drawable_size(ctx);
+                let (w, h) = (VIRTUAL_W, VIRTUAL_H);
                 // 与 draw 中一致的按钮尺寸与位置
                 let btn_w = 220.0;
                 let btn_h = 56.0;
                 let bx = w / 2.0 - btn_w / 2.0;
                 let by = h * 0.55;
                 if x >= bx && x <= bx + btn_w && y >= by && y <= by + btn_h {
-                    // 点击开始按钮 -> 进入游戏
-                    self.reset_game();
-                    self.screen = Screen::Playing;
+                    // 点击开始按钮 -> 选择存档槽
+                    self.scenes.push(Screen::SlotSelect, Transition::Slide);
+                }
+                // Options、排行榜与回放按钮
+                let row_w = 330.0;
+                let obtn_w = (row_w - 20.0) / 3.0;
+                let obtn_h = 40.0;
+                let oby = by + btn_h + 40.0;
+                let ox = w / 2.0 - row_w / 2.0;
+                if x >= ox && x <= ox + obtn_w && y >= oby && y <= oby + obtn_h {
+                    self.scenes.push(Screen::Options, Transition::Slide);
+                }
+                let sx = ox + obtn_w + 10.0;
+                if x >= sx && x <= sx + obtn_w && y >= oby && y <= oby + obtn_h {
+                    self.scenes.push(Screen::Leaderboard, Transition::Slide);
+                }
+                let rx = sx + obtn_w + 10.0;
+                if x >= rx && x <= rx + obtn_w && y >= oby && y <= oby + obtn_h {
+                    let path = self.replay_path.clone();
+                    self.start_playback(&path);
+                }
+            }
+            Screen::SlotSelect => {
+                // 与 draw 中一致的槽位与按钮位置
+                for i in 0..SLOT_COUNT {
+                    if slot_clear_rect(i).contains([x, y]) {
+                        if let Err(e) = save::delete_slot(&save::slot_path(&self.save_dir, i)) {
+                            eprintln!("failed to delete save slot {}: {}", i + 1, e);
+                        } else {
+                            self.slots[i] = Slot::Empty;
+                        }
+                        return;
+                    }
+                    if slot_rect(i).contains([x, y]) {
+                        self.start_slot(ctx, i);
+                        return;
+                    }
+                }
+                let (w, h) = (VIRTUAL_W, VIRTUAL_H);
+                let back = graphics::Rect::new(w / 2.0 - 90.0, h - 60.0, 180.0, 40.0);
+                if back.contains([x, y]) {
+                    self.scenes.pop(Transition::Slide);
+                }
+            }
+            Screen::Leaderboard => {
+                let (w, h) = (VIRTUAL_W, VIRTUAL_H);
+                let btn_w = 180.0;
+                let btn_h = 40.0;
+                let by = h - 60.0;
+                let back = graphics::Rect::new(w / 2.0 - btn_w / 2.0, by, btn_w, btn_h);
+                if back.contains([x, y]) {
+                    self.scenes.pop(Transition::Slide);
+                }
+            }
+            Screen::Options => {
+                let (w, h) = (VIRTUAL_W, VIRTUAL_H);
+                // 与 draw 中一致的行与按钮位置
+                if let Some(i) = (0..SETTING_ROWS.len()).find(|i| setting_row_rect(*i).contains([x, y])) {
+                    self.rebinding = None;
+                    self.change_setting(ctx, i);
+                    return;
+                }
+                for (i, action) in Action::ALL.iter().enumerate() {
+                    if binding_row_rect(i).contains([x, y]) {
+                        // 依次选中该动作的每个按键，最后一项为追加新按键，然后回到第一个
+                        let bindings = &self.input.bindings;
+                        let count = bindings.keys(*action).len().max(bindings.buttons(*action).len());
+                        let index = match self.rebinding {
+                            Some((a, index)) if a == *action => (index + 1) % (count + 1),
+                            _ => 0,
+                        };
+                        self.rebinding = Some((*action, index));
+                        return;
+                    }
+                }
+                let btn_w = 180.0;
+                let btn_h = 40.0;
+                let by = h - 60.0;
+                let back = graphics::Rect::new(w / 2.0 - btn_w - 10.0, by, btn_w, btn_h);
+                let reset = graphics::Rect::new(w / 2.0 + 10.0, by, btn_w, btn_h);
+                if back.contains([x, y]) {
+                    // 返回下层界面（菜单或暂停菜单），设置在 exit_screen 中保存
+                    self.rebinding = None;
+                    self.scenes.pop(Transition::Slide);
+                } else if reset.contains([x, y]) {
+                    self.rebinding = None;
+                    self.input.bindings = Bindings::default();
+                }
+            }
+            Screen::Pause => {
+                let (w, h) = (VIRTUAL_W, VIRTUAL_H);
+                let clicked = (0..PAUSE_ITEMS.len()).find(|i| pause_item_rect(w, h, *i).contains([x, y]));
+                match clicked {
+                    // Resume
+                    Some(0) => self.scenes.pop(Transition::Cut),
+                    // Restart Level：与开始一局时一样重新开始速通计时
+                    Some(1) => {
+                        self.reset_game();
+                        self.speedrun.start(&self.level.id);
+                        self.scenes.pop(Transition::Cut);
+                    }
+                    // Options：压在暂停菜单之上，返回时回到暂停菜单
+                    Some(2) => self.scenes.push(Screen::Options, Transition::Slide),
+                    // Quit to Menu
+                    Some(3) => self.scenes.reset(Screen::Menu, Transition::Fade),
+                    _ => {}
                 }
             }
             Screen::Playing => {
-                let (w, _) = graphics::drawable_size(ctx);
+                let (w, _) = (VIRTUAL_W, VIRTUAL_H);
                 let btn_w = 80.0;
                 let btn_h = 28.0;
                 let bx = w - btn_w - 8.0;
                 let by = 8.0;
                 if x >= bx && x <= bx + btn_w && y >= by && y <= by + btn_h {
-                    // 点击退出按钮 -> 结束本局，返回菜单并重置玩家
-                    self.screen = Screen::Menu;
-                    self.reset_player();
+                    // 点击退出按钮 -> 结束本局，返回菜单（进入菜单时重置玩家）
+                    self.scenes.reset(Screen::Menu, Transition::Fade);
                 }
             }
             Screen::GameOver => {
-                let (w, h) = graphics::drawable_size(ctx);
+                let (w, h) = (VIRTUAL_W, VIRTUAL_H);
                 let btn_w = 140.0;
                 let btn_h = 44.0;
                 let bx = w / 2.0 - btn_w / 2.0;
                 let by = h / 2.0 - btn_h / 2.0;
                 // Restart
                 if x >= bx && x <= bx + btn_w && y >= by && y <= by + btn_h {
-                    self.reset_game();
-                    self.screen = Screen::Playing;
+                    // 重开前先提交名字（之后的 exit_screen 钩子时分数已被重置）
+                    self.submit_score(false);
+                    match self.current_slot {
+                        Some(slot) => self.start_slot(ctx, slot),
+                        None => {
+                            self.world.lives = START_LIVES;
+                            self.campaign = Some(0);
+                            self.reset_game();
+                            self.scenes.reset(Screen::Playing, Transition::Iris);
+                        }
+                    }
                     return;
                 }
                 // Quit (下方)
                 let bx2 = bx;
                 let by2 = by + btn_h + 12.0;
                 if x >= bx2 && x <= bx2 + btn_w && y >= by2 && y <= by2 + btn_h {
-                    self.screen = Screen::Menu;
-                    self.reset_player();
+                    self.scenes.reset(Screen::Menu, Transition::Fade);
                 }
             }
             Screen::Victory => {
-                let (w, h) = graphics::drawable_size(ctx);
-                let btn_w = 180.0;
-                let btn_h = 44.0;
-                let bx = w / 2.0 - btn_w / 2.0;
-                let by = h * 0.6;
-                if x >= bx && x <= bx + btn_w && y >= by && y <= by + btn_h {
-                    self.screen = Screen::Menu;
-                    self.reset_player();
+                let count = if self.has_next_level() { 2 } else { 1 };
+                if victory_button_rect(0, count).contains([x, y]) {
+                    self.scenes.reset(Screen::Menu, Transition::Fade);
+                } else if count == 2 && victory_button_rect(1, count).contains([x, y]) {
+                    self.next_level(ctx);
                 }
             }
         }
     }
+
+    fn key_down_event(&mut self, ctx: &mut Context, keycode: KeyCode, _keymods: KeyMods, _repeat: bool) {
+        // 选项界面等待新按键：Esc 取消，其余按键绑定到该动作
+        if let Some((action, index)) = self.rebinding {
+            if keycode == KeyCode::Escape || self.input.bindings.bind_key(action, index, keycode) {
+                self.rebinding = None;
+            }
+            return;
+        }
+        // 名字输入：退格删除，回车提交
+        if self.name_entry.is_some() {
+            match keycode {
+                KeyCode::Back => {
+                    if let Some(name) = &mut self.name_entry {
+                        name.pop();
+                    }
+                }
+                KeyCode::Return | KeyCode::NumpadEnter => self.submit_score(self.scenes.top() == Screen::Victory),
+                _ => {}
+            }
+            return;
+        }
+        // ` 开关控制台；打开时按键只用于编辑命令行
+        if keycode == KeyCode::Grave {
+            self.console.toggle();
+            return;
+        }
+        if self.console.open {
+            match keycode {
+                KeyCode::Back => self.console.backspace(),
+                KeyCode::Up => self.console.browse(true),
+                KeyCode::Down => self.console.browse(false),
+                KeyCode::Return | KeyCode::NumpadEnter => {
+                    if let Some(line) = self.console.submit() {
+                        self.run_command(ctx, &line);
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+        // 游戏中 F5 快速存档、F9 读取快速存档；回放中不可用（读档后录像的输入就对不上了）
+        if self.scenes.top() == Screen::Playing && !self.scenes.busy() && self.playback.is_none() {
+            match keycode {
+                KeyCode::F5 => self.quick_save(),
+                KeyCode::F9 => self.quick_load(),
+                _ => {}
+            }
+        }
+        // F3 切换调试浮层（任意界面可切换，仅在游戏画面中绘制）
+        if keycode == KeyCode::F3 {
+            self.debug.toggle();
+        }
+        // 菜单中保留 ggez 默认行为：Esc 退出游戏（游戏中 Esc 为暂停键）
+        if keycode == KeyCode::Escape && self.scenes.top() == Screen::Menu {
+            event::quit(ctx);
+        }
+    }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) {
+        if self.console.open {
+            self.console.type_char(character);
+        } else if let Some(name) = &mut self.name_entry
+            && !character.is_control()
+            && name.chars().count() < NAME_LEN
+        {
+            name.push(character);
+        }
+    }
+
+    fn resize_event(&mut self, ctx: &mut Context, _width: f32, _height: f32) {
+        if let Err(e) = fit_screen_coordinates(ctx) {
+            eprintln!("failed to fit screen coordinates: {}", e);
+        }
+    }
+
+    // 窗口失去焦点时自动暂停
+    fn focus_event(&mut self, _ctx: &mut Context, gained: bool) {
+        if !gained && self.scenes.top() == Screen::Playing {
+            self.scenes.push(Screen::Pause, Transition::Cut);
+        }
+    }
+
+    fn gamepad_button_down_event(&mut self, _ctx: &mut Context, btn: Button, id: GamepadId) {
+        if let Some((action, index)) = self.rebinding {
+            if self.input.bindings.bind_button(action, index, btn) {
+                self.rebinding = None;
+            }
+            return;
+        }
+        self.input.button(btn, id, true);
+    }
+
+    fn gamepad_button_up_event(&mut self, _ctx: &mut Context, btn: Button, id: GamepadId) {
+        self.input.button(btn, id, false);
+    }
+
+    fn gamepad_axis_event(&mut self, _ctx: &mut Context, axis: Axis, value: f32, id: GamepadId) {
+        self.input.axis(axis, value, id);
+    }
+}
+
+// 不创建窗口时读取关卡：优先 --level 指定的文件，否则与窗口模式的 Assets::level 一样
+// 读取 resources/levels/<id>.toml（不存在时用内置关卡，格式错误时警告并回退）。
+// 嵌入的资源需要 Context 才能读取，单文件分发时这里直接使用内置关卡
+fn load_level(path: Option<&std::path::Path>, resource_dir: &std::path::Path, id: &str) -> GameResult<Level> {
+    if let Some(path) = path {
+        return Level::load(path);
+    }
+    let path = resource_dir.join("levels").join(format!("{}.toml", id));
+    if !path.is_file() {
+        return Ok(Level::builtin());
+    }
+    Ok(Level::load(&path).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        Level::builtin()
+    }))
+}
+
+// 无窗口地重放录像并核对最终分数与状态哈希（用于回归测试）
+fn verify_replay(replay: &Replay, level: &Level) -> GameResult {
+    if replay.level != level.id {
+        return Err(GameError::CustomError(format!("replay is for level {}, not {}", replay.level, level.id)));
+    }
+    if replay.version != env!("CARGO_PKG_VERSION") {
+        eprintln!("warning: replay was recorded with version {}", replay.version);
+    }
+    let world = World::new(level, VIRTUAL_W, VIRTUAL_H, replay.players(), replay.lives, replay.power);
+    let world = replay::run(replay, world);
+    let hash = world.state_hash();
+    if world.score != replay.final_score || hash != replay.final_hash {
+        return Err(GameError::CustomError(format!(
+            "replay mismatch: score {} (expected {}), hash {:016x} (expected {:016x})",
+            world.score, replay.final_score, hash, replay.final_hash
+        )));
+    }
+    println!("replay ok: {} steps, score {}, hash {:016x}", replay.steps.len(), world.score, hash);
+    Ok(())
+}
+
+// 无窗口地推进模拟（--headless）：输入来自录像，没有录像时玩家不操作；
+// 到达步数上限、录像结束或一局结束时停止，打印结果供脚本使用
+fn run_headless(level: &Level, replay: Option<&Replay>, frames: Option<u64>) -> GameResult {
+    if let Some(replay) = replay
+        && replay.level != level.id
+    {
+        return Err(GameError::CustomError(format!("replay is for level {}, not {}", replay.level, level.id)));
+    }
+    let players = replay.map_or(1, Replay::players);
+    let (lives, power) = replay.map_or((START_LIVES, Power::Small), |r| (r.lives, r.power));
+    let mut world = World::new(level, VIRTUAL_W, VIRTUAL_H, players, lives, power);
+    let mut steps = 0;
+    while world.outcome.is_none() && frames.is_none_or(|n| steps < n) {
+        let inputs = match replay {
+            Some(replay) => match replay.steps.get(steps as usize) {
+                Some(inputs) => inputs.clone(),
+                None => break,
+            },
+            None => vec![PlayerInput::default(); players],
+        };
+        replay::step(&mut world, &inputs);
+        steps += 1;
+    }
+    let outcome = match world.outcome {
+        Some(outcome) => format!("{:?}", outcome),
+        None => "running".to_string(),
+    };
+    println!(
+        "level {}: {} steps ({:.2}s), outcome {}, score {}, coins {}, lives {}, hash {:016x}",
+        level.id,
+        steps,
+        world.level_time,
+        outcome,
+        world.score,
+        world.coin_count,
+        world.lives,
+        world.state_hash()
+    );
+    Ok(())
+}
+
+// 中文字体：优先使用随游戏分发的 resources/fonts/cjk.ttf，其次是系统自带的中文字体
+fn load_cjk_font(ctx: &mut Context) -> Option<graphics::Font> {
+    if let Ok(font) = graphics::Font::new(ctx, "/fonts/cjk.ttf") {
+        return Some(font);
+    }
+    SYSTEM_CJK_FONTS.iter().find_map(|path| {
+        let bytes = std::fs::read(path).ok()?;
+        graphics::Font::new_glyph_font_bytes(ctx, &bytes).map_err(|e| eprintln!("cannot use font {}: {}", path, e)).ok()
+    })
+}
+
+// 散文件资源目录：优先当前目录下的 resources/，其次可执行文件旁的 resources/
+// 都不存在时（单文件分发）只使用嵌入的资源
+fn find_resource_dir() -> std::path::PathBuf {
+    let local = std::path::PathBuf::from("./resources");
+    if local.is_dir() {
+        return local;
+    }
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join("resources")))
+        .filter(|dir| dir.is_dir())
+        .unwrap_or(local)
+}
+
+// 嵌入可执行文件的资源（build.rs 打包）挂在散文件之后，同名文件以散文件为准
+#[cfg(feature = "embed-resources")]
+fn with_embedded_resources(builder: ggez::ContextBuilder) -> ggez::ContextBuilder {
+    builder.add_zipfile_bytes(&include_bytes!(concat!(env!("OUT_DIR"), "/resources.zip"))[..])
+}
+
+#[cfg(not(feature = "embed-resources"))]
+fn with_embedded_resources(builder: ggez::ContextBuilder) -> ggez::ContextBuilder {
+    builder
 }
 
 fn main() -> GameResult {
-    // 把资源目录加入 Context（相对路径为项目根）
-    let resource_dir = std::path::PathBuf::from("./resources");
-    let cb = ggez::ContextBuilder::new("platformer", "example")
-        .add_resource_path(resource_dir)
-        .window_mode(ggez::conf::WindowMode::default().dimensions(800.0, 400.0));
-    let (mut ctx, event_loop) = cb.build()?;
-    let state = GameState::new(&mut ctx)?;
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+    let resource_dir = find_resource_dir();
+    // --verify-replay / --headless：不创建窗口；没有 --level 时使用录像所录的关卡
+    if let Some(path) = &args.verify_replay {
+        let replay = Replay::load(path)?;
+        let level = load_level(args.level.as_deref(), &resource_dir, &replay.level)?;
+        return verify_replay(&replay, &level);
+    }
+    let replay = args.replay.as_deref().map(Replay::load).transpose()?;
+    if args.headless {
+        let id = replay.as_ref().map_or_else(|| Level::builtin().id, |r| r.level.clone());
+        let level = load_level(args.level.as_deref(), &resource_dir, &id)?;
+        return run_headless(&level, replay.as_ref(), args.frames);
+    }
+    let level = args.level.as_deref().map(Level::load).transpose()?;
+    // 设置需要在创建窗口之前读取（窗口大小、全屏、垂直同步）
+    let settings_path = Settings::path(GAME_ID, AUTHOR);
+    let settings = Settings::load(&settings_path);
+    // 命令行指定的窗口大小与全屏只在本次生效，不写入设置
+    let mut window_mode = settings.window_mode(VIRTUAL_W, VIRTUAL_H);
+    if let Some((w, h)) = args.window {
+        window_mode = window_mode.dimensions(w, h);
+    }
+    if args.fullscreen {
+        window_mode = window_mode.fullscreen_type(ggez::conf::FullscreenType::Desktop);
+    }
+    let builder = |audio: bool| {
+        with_embedded_resources(ggez::ContextBuilder::new(GAME_ID, AUTHOR))
+            .add_resource_path(resource_dir.clone())
+            .window_setup(ggez::conf::WindowSetup::default().vsync(settings.vsync))
+            .window_mode(window_mode)
+            .modules(ggez::conf::ModuleConf::default().audio(audio))
+    };
+    // 没有音频设备时（如无头 CI）关闭音频模块后重试，游戏静音运行
+    let (mut ctx, event_loop, audio_enabled) = match builder(true).build() {
+        Ok((ctx, event_loop)) => (ctx, event_loop, true),
+        Err(ggez::GameError::AudioError(e)) => {
+            eprintln!("no audio device ({}), running without sound", e);
+            let (ctx, event_loop) = builder(false).build()?;
+            (ctx, event_loop, false)
+        }
+        Err(e) => return Err(e),
+    };
+    fit_screen_coordinates(&mut ctx)?;
+    let mut state = GameState::new(&mut ctx, settings, settings_path, resource_dir, audio_enabled)?;
+    if let Some(seed) = args.seed {
+        state.particles.reseed(seed);
+    }
+    if let Some(level) = level {
+        state.set_level(&ctx, level);
+        if let Some(path) = &args.level {
+            state.assets.watch_level_file(path);
+        }
+    } else if let Some(replay) = &replay
+        && replay.level != state.level.id
+    {
+        let level = state.assets.level(&mut ctx, &replay.level);
+        state.set_level(&ctx, level);
+    }
+    if let Some(path) = &args.replay {
+        state.start_playback(path);
+    } else if args.skip_menu {
+        state.campaign = Some(0);
+        state.reset_game();
+        state.scenes.reset(Screen::Playing, Transition::Cut);
+    }
     event::run(ctx, event_loop, state)
 }
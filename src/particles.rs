@@ -0,0 +1,167 @@
+use ggez::graphics::{self, Color, DrawMode, DrawParam, MeshBuilder, Rect};
+use ggez::{Context, GameResult};
+
+// 同时存在的粒子上限，超出时丢弃新发射的粒子
+const MAX_PARTICLES: usize = 512;
+
+// 发射器参数：一次性发射 count 个粒子，方向在 angle 范围内（弧度，0 为向右，负值向上）
+pub struct Emitter {
+    pub count: usize,
+    pub speed: (f32, f32),
+    pub angle: (f32, f32),
+    pub life: (f32, f32),
+    pub gravity: f32,
+    pub size: f32,
+    // 颜色与透明度随生命周期从 start 渐变到 end
+    pub start: Color,
+    pub end: Color,
+}
+
+// 金币拾取时的闪光
+pub const COIN_SPARKLE: Emitter = Emitter {
+    count: 10,
+    speed: (40.0, 110.0),
+    angle: (-std::f32::consts::PI, 0.0),
+    life: (0.3, 0.6),
+    gravity: 0.0,
+    size: 3.0,
+    start: Color::new(1.0, 0.95, 0.5, 1.0),
+    end: Color::new(1.0, 1.0, 1.0, 0.0),
+};
+
+// 特殊方块被顶开时的碎片
+pub const BLOCK_DEBRIS: Emitter = Emitter {
+    count: 12,
+    speed: (120.0, 220.0),
+    angle: (-std::f32::consts::PI * 0.85, -std::f32::consts::PI * 0.15),
+    life: (0.6, 0.9),
+    gravity: 900.0,
+    size: 5.0,
+    start: Color::new(0.75, 0.45, 0.2, 1.0),
+    end: Color::new(0.5, 0.3, 0.15, 0.0),
+};
+
+// 落地与急转时脚下的尘土
+pub const DUST: Emitter = Emitter {
+    count: 6,
+    speed: (20.0, 60.0),
+    angle: (-std::f32::consts::PI, 0.0),
+    life: (0.25, 0.45),
+    gravity: -30.0,
+    size: 4.0,
+    start: Color::new(0.9, 0.9, 0.85, 0.8),
+    end: Color::new(0.9, 0.9, 0.85, 0.0),
+};
+
+// 怪物被踩扁时的爆散
+pub const MONSTER_BURST: Emitter = Emitter {
+    count: 16,
+    speed: (80.0, 180.0),
+    angle: (-std::f32::consts::PI, std::f32::consts::PI),
+    life: (0.4, 0.7),
+    gravity: 400.0,
+    size: 4.0,
+    start: Color::new(0.6, 0.2, 0.7, 1.0),
+    end: Color::new(1.0, 0.4, 0.4, 0.0),
+};
+
+struct Particle {
+    x: f32,
+    y: f32,
+    vx: f32,
+    vy: f32,
+    age: f32,
+    life: f32,
+    gravity: f32,
+    size: f32,
+    start: Color,
+    end: Color,
+}
+
+// 纯视觉效果，不进入 World（不影响快照与录像）
+pub struct Particles {
+    particles: Vec<Particle>,
+    // 简单的线性同余随机数，避免引入 rand 依赖
+    seed: u32,
+}
+
+impl Default for Particles {
+    fn default() -> Self {
+        Self { particles: Vec::new(), seed: 0x1234_5678 }
+    }
+}
+
+impl Particles {
+    // 命令行 --seed：固定视觉效果的随机序列
+    pub fn reseed(&mut self, seed: u32) {
+        self.seed = seed;
+    }
+
+    // [0, 1) 的伪随机数
+    fn random(&mut self) -> f32 {
+        self.seed = self.seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        (self.seed >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    fn range(&mut self, (min, max): (f32, f32)) -> f32 {
+        min + (max - min) * self.random()
+    }
+
+    pub fn emit(&mut self, emitter: &Emitter, x: f32, y: f32) {
+        for _ in 0..emitter.count {
+            if self.particles.len() >= MAX_PARTICLES {
+                return;
+            }
+            let speed = self.range(emitter.speed);
+            let angle = self.range(emitter.angle);
+            let life = self.range(emitter.life);
+            self.particles.push(Particle {
+                x,
+                y,
+                vx: angle.cos() * speed,
+                vy: angle.sin() * speed,
+                age: 0.0,
+                life,
+                gravity: emitter.gravity,
+                size: emitter.size,
+                start: emitter.start,
+                end: emitter.end,
+            });
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for p in &mut self.particles {
+            p.vy += p.gravity * dt;
+            p.x += p.vx * dt;
+            p.y += p.vy * dt;
+            p.age += dt;
+        }
+        self.particles.retain(|p| p.age < p.life);
+    }
+
+    // 所有粒子合并为一个 Mesh 一次绘制
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        if self.particles.is_empty() {
+            return Ok(());
+        }
+        let mut builder = MeshBuilder::new();
+        for p in &self.particles {
+            let k = p.age / p.life;
+            let color = Color::new(
+                p.start.r + (p.end.r - p.start.r) * k,
+                p.start.g + (p.end.g - p.start.g) * k,
+                p.start.b + (p.end.b - p.start.b) * k,
+                p.start.a + (p.end.a - p.start.a) * k,
+            );
+            let half = p.size / 2.0;
+            builder.rectangle(DrawMode::fill(), Rect::new(p.x - half, p.y - half, p.size, p.size), color)?;
+        }
+        let mesh = builder.build(ctx)?;
+        graphics::draw(ctx, &mesh, DrawParam::default())
+    }
+}
@@ -0,0 +1,76 @@
+use std::fs;
+use std::path::Path;
+
+use ggez::{GameError, GameResult};
+use serde::{Deserialize, Serialize};
+
+use crate::save::Power;
+use crate::world::{Event, PlayerInput, World};
+
+// 模拟固定以 60 步/秒推进，录像按步记录输入
+pub const STEPS_PER_SEC: u32 = 60;
+pub const FIXED_DT: f32 = 1.0 / STEPS_PER_SEC as f32;
+
+// 一局的输入录像：起始状态 + 每一步各玩家的输入 + 结束时的分数与状态哈希
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: String,
+    pub level: String,
+    pub lives: u32,
+    pub power: Power,
+    pub steps: Vec<Vec<PlayerInput>>,
+    pub final_score: i32,
+    pub final_hash: u64,
+}
+
+impl Replay {
+    pub fn new(level: &str, world: &World) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            level: level.to_string(),
+            lives: world.lives,
+            power: world.power,
+            steps: Vec::new(),
+            final_score: 0,
+            final_hash: 0,
+        }
+    }
+
+    // 录制开始时的玩家数（之后的变化体现在每一步的输入长度上）
+    pub fn players(&self) -> usize {
+        self.steps.first().map_or(1, |s| s.len().max(1))
+    }
+
+    pub fn finish(&mut self, world: &World) {
+        self.final_score = world.score;
+        self.final_hash = world.state_hash();
+    }
+
+    pub fn load(path: &Path) -> GameResult<Self> {
+        let text = fs::read_to_string(path)?;
+        serde_json::from_str(&text).map_err(|e| GameError::CustomError(e.to_string()))
+    }
+
+    pub fn save(&self, path: &Path) -> GameResult {
+        let text = serde_json::to_string(self).map_err(|e| GameError::CustomError(e.to_string()))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, text)?;
+        Ok(())
+    }
+}
+
+// 推进一步：玩家数随录像中的输入变化（手柄热插拔）
+pub fn step(world: &mut World, inputs: &[PlayerInput]) -> Vec<Event> {
+    world.set_player_count(inputs.len());
+    world.update(FIXED_DT, inputs)
+}
+
+// 无窗口地重放录像，返回最终状态
+pub fn run(replay: &Replay, mut world: World) -> World {
+    for inputs in &replay.steps {
+        step(&mut world, inputs);
+    }
+    world
+}
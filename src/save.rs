@@ -0,0 +1,135 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use ggez::{GameError, GameResult};
+use serde::{Deserialize, Serialize};
+use toml::value::Table;
+
+pub const SLOT_COUNT: usize = 3;
+pub const START_LIVES: u32 = 3;
+
+// 存档格式迁移：MIGRATIONS[i] 把第 i+1 版的存档升级到第 i+2 版。
+// 修改 SaveData 的字段时在末尾追加一个迁移函数，版本号随之加一
+const MIGRATIONS: &[fn(&mut Table)] = &[];
+pub const SAVE_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Power {
+    #[default]
+    Small,
+    Big,
+    Fire,
+}
+
+// TOML 要求普通值写在表之前，所以两个 BTreeMap 字段放在最后
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SaveData {
+    pub version: u32,
+    // 可以开始的关卡（通关时解锁 Level::next）
+    pub unlocked_levels: BTreeSet<String>,
+    // 通关时已顶开的特殊方块，记为 "<关卡>:<col>,<row>"
+    pub secrets: BTreeSet<String>,
+    pub lives: u32,
+    pub power: Power,
+    pub best_scores: BTreeMap<String, u32>,
+    // 通关用时（秒）
+    pub best_times: BTreeMap<String, f32>,
+}
+
+impl SaveData {
+    pub fn new(first_level: &str) -> Self {
+        Self {
+            version: SAVE_VERSION,
+            unlocked_levels: BTreeSet::from([first_level.to_string()]),
+            secrets: BTreeSet::new(),
+            lives: START_LIVES,
+            power: Power::Small,
+            best_scores: BTreeMap::new(),
+            best_times: BTreeMap::new(),
+        }
+    }
+
+    // 通关时记录最好成绩
+    pub fn record_level(&mut self, level: &str, score: u32, time: f32) {
+        let best = self.best_scores.entry(level.to_string()).or_insert(0);
+        *best = (*best).max(score);
+        let best = self.best_times.entry(level.to_string()).or_insert(time);
+        *best = best.min(time);
+    }
+
+    pub fn unlock(&mut self, level: &str) {
+        self.unlocked_levels.insert(level.to_string());
+    }
+
+    pub fn record_secret(&mut self, level: &str, (col, row): (usize, usize)) {
+        self.secrets.insert(format!("{}:{},{}", level, col, row));
+    }
+
+    // 某一关已找到的秘密数量
+    pub fn secrets_in(&self, level: &str) -> usize {
+        let prefix = format!("{}:", level);
+        self.secrets.iter().filter(|s| s.starts_with(&prefix)).count()
+    }
+}
+
+// 存档槽的状态：损坏或版本过新的存档不会被覆盖，只能在槽位界面手动清除
+pub enum Slot {
+    Empty,
+    Loaded(SaveData),
+    Unreadable,
+}
+
+pub fn slot_path(dir: &Path, slot: usize) -> PathBuf {
+    dir.join(format!("slot{}.toml", slot + 1))
+}
+
+pub fn load_slot(path: &Path) -> Slot {
+    let Ok(text) = fs::read_to_string(path) else {
+        return Slot::Empty;
+    };
+    match parse(&text, MIGRATIONS) {
+        Ok(data) => Slot::Loaded(data),
+        Err(e) => {
+            eprintln!("cannot read save {}: {}", path.display(), e);
+            Slot::Unreadable
+        }
+    }
+}
+
+// 解析存档并升级到最新版本；migrations 即 MIGRATIONS，测试时可以换成自己的迁移
+pub fn parse(text: &str, migrations: &[fn(&mut Table)]) -> GameResult<SaveData> {
+    let latest = migrations.len() as i64 + 1;
+    let mut table: Table = toml::from_str(text).map_err(|e| GameError::ConfigError(e.to_string()))?;
+    let version = table.get("version").and_then(|v| v.as_integer()).unwrap_or(1);
+    if version < 1 || version > latest {
+        return Err(GameError::ConfigError(format!("unsupported save version {}", version)));
+    }
+    for migrate in &migrations[version as usize - 1..] {
+        migrate(&mut table);
+    }
+    table.insert("version".to_string(), toml::Value::Integer(latest));
+    toml::Value::Table(table).try_into().map_err(|e: toml::de::Error| GameError::ConfigError(e.to_string()))
+}
+
+// 先写临时文件并落盘，再原子地改名覆盖，写到一半崩溃也不会损坏旧存档
+pub fn write_slot(path: &Path, data: &SaveData) -> GameResult {
+    let text = toml::to_string(data).map_err(|e| GameError::ConfigError(e.to_string()))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("toml.tmp");
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(text.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+pub fn delete_slot(path: &Path) -> GameResult {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
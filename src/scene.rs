@@ -0,0 +1,183 @@
+use ggez::graphics::{self, DrawMode, DrawParam, Rect};
+use ggez::{Context, GameResult};
+
+use crate::world::Outcome;
+
+// 过渡时长（秒）：前半段遮住旧界面，到中点时切换，后半段揭开新界面
+const TRANSITION_SECS: f32 = 0.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Screen {
+    Menu,
+    Playing,
+    // 暂停菜单，压在 Playing 之上
+    Pause,
+    Options,
+    Leaderboard,
+    SlotSelect,
+    GameOver,
+    Victory,
+}
+
+impl Screen {
+    // 浮层界面：绘制时下层界面也要画出（但下层不更新）
+    pub fn is_overlay(self) -> bool {
+        matches!(self, Screen::Pause | Screen::Victory)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transition {
+    // 无动画，下一帧直接切换
+    Cut,
+    Fade,
+    // 圆形遮罩向屏幕中心收拢再张开
+    Iris,
+    Slide,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Change {
+    Push(Screen),
+    Pop,
+    Replace(Screen),
+    // 清空整个栈后进入
+    Reset(Screen),
+}
+
+// 一次切换中离开与进入的界面，由外层调用对应的 exit/enter 钩子
+#[derive(Default)]
+pub struct Switch {
+    pub exited: Vec<Screen>,
+    pub entered: Option<Screen>,
+}
+
+// 界面栈与界面间的过渡
+pub struct Scenes {
+    stack: Vec<Screen>,
+    pending: Option<(Change, Transition)>,
+    // 过渡进度 0..1；applied 表示已过中点并完成切换
+    progress: f32,
+    applied: bool,
+}
+
+impl Scenes {
+    pub fn new(screen: Screen) -> Self {
+        Self { stack: vec![screen], pending: None, progress: 0.0, applied: false }
+    }
+
+    pub fn top(&self) -> Screen {
+        *self.stack.last().expect("scene stack is never empty")
+    }
+
+    pub fn contains(&self, screen: Screen) -> bool {
+        self.stack.contains(&screen)
+    }
+
+    // 需要绘制的界面（自下而上）：最上层的非浮层界面及其上的浮层
+    pub fn visible(&self) -> &[Screen] {
+        let start = self.stack.iter().rposition(|s| !s.is_overlay()).unwrap_or(0);
+        &self.stack[start..]
+    }
+
+    // 正在过渡：期间不响应点击、不推进界面逻辑
+    pub fn busy(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    pub fn push(&mut self, screen: Screen, transition: Transition) {
+        self.start(Change::Push(screen), transition);
+    }
+
+    pub fn pop(&mut self, transition: Transition) {
+        self.start(Change::Pop, transition);
+    }
+
+    pub fn replace(&mut self, screen: Screen, transition: Transition) {
+        self.start(Change::Replace(screen), transition);
+    }
+
+    pub fn reset(&mut self, screen: Screen, transition: Transition) {
+        self.start(Change::Reset(screen), transition);
+    }
+
+    // 一局结束：胜利界面压在游戏画面之上，失败界面替换游戏画面
+    pub fn finish(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Victory => self.push(Screen::Victory, Transition::Fade),
+            Outcome::GameOver => self.replace(Screen::GameOver, Transition::Fade),
+        }
+    }
+
+    // 过渡进行中再次请求切换时忽略，避免连点造成错乱
+    fn start(&mut self, change: Change, transition: Transition) {
+        if self.pending.is_none() {
+            self.pending = Some((change, transition));
+            self.progress = 0.0;
+            self.applied = false;
+        }
+    }
+
+    // 推进过渡；到达中点时修改栈并返回这次切换
+    pub fn update(&mut self, dt: f32) -> Option<Switch> {
+        let (change, transition) = self.pending?;
+        self.progress = if transition == Transition::Cut { 1.0 } else { (self.progress + dt / TRANSITION_SECS).min(1.0) };
+        let mut switch = None;
+        if self.progress >= 0.5 && !self.applied {
+            self.applied = true;
+            switch = Some(self.apply(change));
+        }
+        if self.progress >= 1.0 {
+            self.pending = None;
+        }
+        switch
+    }
+
+    fn apply(&mut self, change: Change) -> Switch {
+        let mut switch = Switch::default();
+        match change {
+            Change::Push(screen) => {
+                self.stack.push(screen);
+                switch.entered = Some(screen);
+            }
+            // 栈底界面不会被弹出
+            Change::Pop if self.stack.len() > 1 => switch.exited.extend(self.stack.pop()),
+            Change::Pop => {}
+            Change::Replace(screen) => {
+                switch.exited.extend(self.stack.pop());
+                self.stack.push(screen);
+                switch.entered = Some(screen);
+            }
+            Change::Reset(screen) => {
+                switch.exited = self.stack.drain(..).rev().collect();
+                self.stack.push(screen);
+                switch.entered = Some(screen);
+            }
+        }
+        switch
+    }
+
+    // 在所有界面之上绘制过渡遮罩
+    pub fn draw(&self, ctx: &mut Context, w: f32, h: f32) -> GameResult {
+        let Some((_, transition)) = self.pending else {
+            return Ok(());
+        };
+        // 遮挡程度：0 → 1（中点）→ 0
+        let cover = 1.0 - (self.progress * 2.0 - 1.0).abs();
+        let black = graphics::Color::BLACK;
+        let mesh = match transition {
+            Transition::Cut => return Ok(()),
+            Transition::Fade => graphics::Mesh::new_rectangle(ctx, DrawMode::fill(), Rect::new(0.0, 0.0, w, h), graphics::Color::new(0.0, 0.0, 0.0, cover))?,
+            Transition::Iris => {
+                // 用很粗的圆环描边实现“中间留一个圆洞”的遮罩
+                let max_r = (w * w + h * h).sqrt() / 2.0;
+                let hole = (1.0 - cover) * max_r;
+                graphics::Mesh::new_circle(ctx, DrawMode::stroke(max_r * 2.0), [w / 2.0, h / 2.0], hole + max_r, 0.5, black)?
+            }
+            // 前半段黑幕从右侧滑入，后半段向左滑出
+            Transition::Slide if !self.applied => graphics::Mesh::new_rectangle(ctx, DrawMode::fill(), Rect::new(w * (1.0 - cover), 0.0, w * cover + 1.0, h), black)?,
+            Transition::Slide => graphics::Mesh::new_rectangle(ctx, DrawMode::fill(), Rect::new(0.0, 0.0, w * cover + 1.0, h), black)?,
+        };
+        graphics::draw(ctx, &mesh, DrawParam::default())
+    }
+}
@@ -0,0 +1,197 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use directories::ProjectDirs;
+use ggez::conf::{FullscreenType, WindowMode};
+use ggez::{GameError, GameResult};
+use serde::{Deserialize, Serialize};
+
+// 可选的窗口缩放倍数（相对虚拟分辨率）
+pub const SCALES: [f32; 3] = [1.0, 1.5, 2.0];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    English,
+    Chinese,
+}
+
+impl Language {
+    pub fn label(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Chinese => "中文",
+        }
+    }
+
+    // 以英文原文为键查找译文；没有译文时原样返回
+    pub fn tr(self, text: &'static str) -> &'static str {
+        if self == Language::English {
+            return text;
+        }
+        match text {
+            "START" => "开始",
+            "Click START to play" => "点击开始进行游戏",
+            "OPTIONS" => "选项",
+            "Options" => "选项",
+            "BACK" => "返回",
+            "RESET" => "重置",
+            "QUIT" => "退出",
+            "MENU" => "菜单",
+            "NEXT" => "下一关",
+            "Paused" => "暂停",
+            "Resume" => "继续",
+            "Restart Level" => "重新开始本关",
+            "Quit to Menu" => "返回主菜单",
+            "You Win!" => "胜利！",
+            "Score" => "分数",
+            "Game Over" => "游戏结束",
+            "Restart" => "重新开始",
+            "Quit" => "退出",
+            "Display" => "显示模式",
+            "Windowed" => "窗口",
+            "Fullscreen" => "全屏",
+            "Scale" => "缩放",
+            "VSync" => "垂直同步",
+            "On (restart)" => "开（重启生效）",
+            "Off (restart)" => "关（重启生效）",
+            "Music" => "音乐",
+            "SFX" => "音效",
+            "Language" => "语言",
+            "press a key or button (click: next, Esc: cancel)" => "请按下按键或手柄按钮（点击：下一个，Esc：取消）",
+            "SCORES" => "排行榜",
+            "High Scores" => "排行榜",
+            "Level" => "关卡",
+            "Campaign" => "整局",
+            "New high score!" => "新纪录！",
+            "Name" => "名字",
+            "Enter to save" => "回车保存",
+            "Select a save slot" => "选择存档",
+            "Slot" => "存档",
+            "Empty" => "空",
+            "unreadable save" => "存档无法读取",
+            "lives" => "生命",
+            "best" => "最佳",
+            "secrets" => "秘密",
+            "REPLAY" => "回放",
+            "Ghost" => "幽灵",
+            "On" => "开",
+            "Off" => "关",
+            "Time" => "时间",
+            "SCORE" => "分数",
+            "COINS" => "金币",
+            "WORLD" => "关卡",
+            "POWER" => "状态",
+            "TIME" => "时间",
+            "LIVES" => "生命",
+            "Small" => "小",
+            "Big" => "大",
+            "Fire" => "火焰",
+            _ => text,
+        }
+    }
+}
+
+// 玩家设置：启动时读取并应用，在选项界面修改后保存
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub fullscreen: bool,
+    pub scale: f32,
+    pub vsync: bool,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub language: Language,
+    // 是否显示本关最快通关的幽灵
+    pub ghost: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            fullscreen: false,
+            scale: 1.0,
+            vsync: true,
+            music_volume: 0.7,
+            sfx_volume: 1.0,
+            language: Language::English,
+            ghost: true,
+        }
+    }
+}
+
+impl Settings {
+    // 与 ggez 的 user_config_dir 相同的目录，这样在创建 Context 之前就能读取设置
+    pub fn path(game_id: &str, author: &str) -> PathBuf {
+        ProjectDirs::from("", author, game_id)
+            .map(|dirs| dirs.config_dir().join("settings.toml"))
+            .unwrap_or_else(|| PathBuf::from("settings.toml"))
+    }
+
+    // 文件缺失或损坏时使用默认设置；超出范围的缩放与音量收回到支持的范围内
+    pub fn load(path: &Path) -> Self {
+        let Ok(text) = fs::read_to_string(path) else {
+            return Settings::default();
+        };
+        match toml::from_str::<Settings>(&text) {
+            Ok(settings) => settings.clamped(),
+            Err(e) => {
+                eprintln!("ignoring invalid settings file {}: {}", path.display(), e);
+                Settings::default()
+            }
+        }
+    }
+
+    fn clamped(mut self) -> Self {
+        let (min, max) = (SCALES[0], SCALES[SCALES.len() - 1]);
+        if !(min..=max).contains(&self.scale) {
+            let scale = if self.scale.is_nan() { Settings::default().scale } else { self.scale.clamp(min, max) };
+            eprintln!("settings: scale {} is out of range, using {}", self.scale, scale);
+            self.scale = scale;
+        }
+        for volume in [&mut self.music_volume, &mut self.sfx_volume] {
+            *volume = if volume.is_nan() { 1.0 } else { volume.clamp(0.0, 1.0) };
+        }
+        self
+    }
+
+    pub fn save(&self, path: &Path) -> GameResult {
+        let text = toml::to_string(self).map_err(|e| GameError::ConfigError(e.to_string()))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    pub fn window_mode(&self, base_w: f32, base_h: f32) -> WindowMode {
+        let fullscreen_type = if self.fullscreen { FullscreenType::Desktop } else { FullscreenType::Windowed };
+        WindowMode::default()
+            .dimensions(base_w * self.scale, base_h * self.scale)
+            .fullscreen_type(fullscreen_type)
+    }
+
+    pub fn next_scale(&mut self) {
+        let idx = SCALES.iter().position(|s| (*s - self.scale).abs() < 0.01).map_or(0, |i| i + 1);
+        self.scale = SCALES[idx % SCALES.len()];
+    }
+}
+
+// 音量以 10% 为一档循环调节：0% -> 10% -> ... -> 100% -> 0%
+pub fn next_volume(volume: f32) -> f32 {
+    if volume >= 0.95 { 0.0 } else { ((volume * 10.0).round() + 1.0) / 10.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Settings;
+
+    #[test]
+    fn out_of_range_scale_is_clamped() {
+        let path = std::env::temp_dir().join(format!("settings-{}.toml", std::process::id()));
+        for (text, scale) in [("scale = 0.0", 1.0), ("scale = -3.0", 1.0), ("scale = 50.0", 2.0), ("scale = 1.5", 1.5)] {
+            std::fs::write(&path, text).unwrap();
+            assert_eq!(Settings::load(&path).scale, scale, "{}", text);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
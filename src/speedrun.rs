@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ggez::{GameError, GameResult};
+use serde::{Deserialize, Serialize};
+
+// 一个分段：到达该点时的累计用时（秒）
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Split {
+    pub name: String,
+    pub time: f32,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Splits {
+    // 个人最佳（PB）的各分段
+    pb: Vec<Split>,
+    // 最近一次跑完的各分段
+    last: Vec<Split>,
+}
+
+// splits.json：按起始关卡分别保存（从存档继续时可能从任意已解锁的关卡开始）
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SplitsFile {
+    runs: BTreeMap<String, Splits>,
+}
+
+// 速通计时：点击 START 后开始，只在暂停菜单中停表；沿 Level::next 连续闯关，
+// 每通过一关（进入管道）记录一个以关卡 id 命名的分段，按名字与同一起点的 PB 比较
+pub struct Speedrun {
+    running: bool,
+    time: f32,
+    // 本次计时的起始关卡
+    start: String,
+    splits: Vec<Split>,
+    file: SplitsFile,
+    path: PathBuf,
+}
+
+impl Speedrun {
+    pub fn load(path: PathBuf) -> Self {
+        let file = match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                eprintln!("ignoring invalid splits file {}: {}", path.display(), e);
+                SplitsFile::default()
+            }),
+            Err(_) => SplitsFile::default(),
+        };
+        Self { running: false, time: 0.0, start: String::new(), splits: Vec::new(), file, path }
+    }
+
+    pub fn start(&mut self, level: &str) {
+        self.running = true;
+        self.time = 0.0;
+        self.start = level.to_string();
+        self.splits.clear();
+    }
+
+    // 中止本次计时（失败、中途退出或读档），不记录
+    pub fn stop(&mut self) {
+        self.running = false;
+    }
+
+    pub fn running(&self) -> bool {
+        self.running
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        if self.running {
+            self.time += dt;
+        }
+    }
+
+    pub fn split(&mut self, name: &str) {
+        if self.running {
+            self.splits.push(Split { name: name.to_string(), time: self.time });
+        }
+    }
+
+    fn pb(&self, name: &str) -> Option<&Split> {
+        self.file.runs.get(&self.start)?.pb.iter().find(|s| s.name == name)
+    }
+
+    // 与 PB 同名分段的差值（负数表示领先）：已落后于 PB 的下一分段（next 为正在进行的关卡），
+    // 否则为已到达的最近分段
+    pub fn delta(&self, next: &str) -> Option<f32> {
+        if self.running
+            && let Some(pb) = self.pb(next)
+            && self.time > pb.time
+        {
+            return Some(self.time - pb.time);
+        }
+        let last = self.splits.last()?;
+        self.pb(&last.name).map(|pb| last.time - pb.time)
+    }
+
+    // 跑完全程：保存本次分段；比 PB 快，或路线（各分段名字）与 PB 不同时成为新的 PB
+    pub fn finish(&mut self) {
+        if !self.running {
+            return;
+        }
+        self.running = false;
+        let Some(run) = self.splits.last() else {
+            return;
+        };
+        let entry = self.file.runs.entry(self.start.clone()).or_default();
+        let same_route = entry.pb.iter().map(|s| &s.name).eq(self.splits.iter().map(|s| &s.name));
+        if !same_route || entry.pb.last().is_none_or(|pb| run.time < pb.time) {
+            entry.pb = self.splits.clone();
+        }
+        entry.last = self.splits.clone();
+        if let Err(e) = save(&self.path, &self.file) {
+            eprintln!("failed to save splits: {}", e);
+        }
+    }
+}
+
+fn save(path: &Path, file: &SplitsFile) -> GameResult {
+    let text = serde_json::to_string_pretty(file).map_err(|e| GameError::CustomError(e.to_string()))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, text)?;
+    Ok(())
+}
+
+// 计时显示格式 m:ss.cc
+pub fn format_time(t: f32) -> String {
+    let cs = (t.max(0.0) * 100.0).round() as u32;
+    format!("{}:{:02}.{:02}", cs / 6000, cs / 100 % 60, cs % 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Speedrun;
+
+    fn run(speedrun: &mut Speedrun, start: &str, splits: &[(&str, f32)]) {
+        speedrun.start(start);
+        for (name, time) in splits {
+            speedrun.tick(time - speedrun.time());
+            speedrun.split(name);
+        }
+        speedrun.finish();
+    }
+
+    // PB 按起始关卡分开保存，分段按名字比较
+    #[test]
+    fn personal_bests_are_kept_per_starting_level() {
+        let path = std::env::temp_dir().join(format!("splits-{}.json", std::process::id()));
+        let mut speedrun = Speedrun::load(path.clone());
+        run(&mut speedrun, "1-1", &[("1-1", 30.0), ("1-2", 70.0)]);
+        run(&mut speedrun, "1-2", &[("1-2", 20.0)]);
+        // 从 1-1 开始的新一轮不会和从 1-2 开始的 PB 比较
+        speedrun.start("1-1");
+        speedrun.tick(25.0);
+        speedrun.split("1-1");
+        assert_eq!(speedrun.delta("1-2"), Some(-5.0));
+        speedrun.tick(50.0);
+        assert_eq!(speedrun.delta("1-2"), Some(5.0), "behind the 1-2 split while still running");
+        speedrun.split("1-2");
+        speedrun.finish();
+        // 较慢的一轮不覆盖 PB，重新读取后仍在
+        let mut speedrun = Speedrun::load(path.clone());
+        std::fs::remove_file(&path).unwrap();
+        speedrun.start("1-1");
+        speedrun.tick(31.0);
+        speedrun.split("1-1");
+        assert_eq!(speedrun.delta("1-2"), Some(1.0));
+    }
+}
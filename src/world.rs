@@ -0,0 +1,590 @@
+use ggez::graphics::Rect;
+use ggez::{GameError, GameResult};
+use serde::{Deserialize, Serialize};
+
+use crate::level::Level;
+use crate::save::Power;
+
+// 虚拟分辨率：所有布局与物理都以此为准，窗口缩放/全屏时等比缩放并留黑边
+pub const VIRTUAL_W: f32 = 800.0;
+pub const VIRTUAL_H: f32 = 400.0;
+
+pub const COIN_SIZE: f32 = 16.0;
+pub const TILE_SIZE: f32 = 32.0;
+pub const GRAVITY: f32 = 1200.0;
+pub const MOVE_SPEED: f32 = 200.0;
+pub const RUN_SPEED: f32 = 320.0;
+pub const JUMP_V: f32 = -420.0;
+// 剩余时间低于此值时 HUD 警告
+pub const LOW_TIME: f32 = 20.0;
+// 通关时每剩余一秒奖励的分数
+pub const TIME_BONUS: i32 = 10;
+// 得分：金币、踩扁怪物、顶开特殊方块
+pub const COIN_POINTS: i32 = 10;
+pub const STOMP_POINTS: i32 = 100;
+pub const BLOCK_POINTS: i32 = 50;
+// 每收集这么多金币奖励一条命
+pub const COINS_PER_LIFE: u32 = 100;
+// 踩怪判定：上一步玩家底部不低于怪物顶部下方这么多像素，且正在下落
+const STOMP_TOLERANCE: f32 = 4.0;
+// 踩扁怪物后的弹起速度
+const STOMP_BOUNCE: f32 = JUMP_V * 0.6;
+// 落地速度超过此值时扬起尘土
+const LAND_DUST_VY: f32 = 300.0;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Player {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+    pub vx: f32,
+    pub vy: f32,
+    pub on_ground: bool,
+}
+
+impl Player {
+    // 按槽位错开出生点，避免多个玩家重叠
+    pub fn spawn(slot: usize) -> Self {
+        Player {
+            x: 50.0 + slot as f32 * 32.0,
+            y: 50.0,
+            w: 24.0,
+            h: 30.0,
+            vx: 0.0,
+            vy: 0.0,
+            on_ground: false,
+        }
+    }
+
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.x, self.y, self.w, self.h)
+    }
+}
+
+// 小怪兽结构体：带有巡逻范围
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Monster {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+    pub vx: f32,
+    pub range_min: f32,
+    pub range_max: f32,
+}
+
+impl Monster {
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.x, self.y, self.w, self.h)
+    }
+}
+
+// 一名玩家在一步模拟中的输入（已经过键位映射）
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerInput {
+    pub move_x: f32,
+    pub jump: bool,
+    pub run: bool,
+    // 已从键位映射读取，模拟中暂未使用
+    #[serde(default)]
+    pub fire: bool,
+}
+
+// 一步模拟中发生的事件，由外层播放音效、切换界面、显示飘字
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    Jump,
+    // 坐标均为世界坐标，供外层显示粒子与飘字
+    Coin { x: f32, y: f32 },
+    Bump,
+    // 特殊方块被顶开
+    BlockOpened { x: f32, y: f32 },
+    Stomp { x: f32, y: f32 },
+    // 玩家重重落地 / 奔跑中急转
+    Land { x: f32, y: f32 },
+    Skid { x: f32, y: f32 },
+    PipeEnter,
+    // 在 (x, y) 处得分
+    Points { x: f32, y: f32, points: i32 },
+    // 奖励一条命
+    ExtraLife { x: f32, y: f32 },
+    // 失去一条命并重生
+    Died,
+    // 剩余时间进入警告区
+    TimeWarning,
+    Victory,
+    GameOver,
+}
+
+// 开发者控制台打开的作弊开关
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Cheats {
+    // 不会因怪物或时间用完而失去生命
+    pub god: bool,
+    // 无重力、穿过方块飞行
+    pub noclip: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Outcome {
+    Victory,
+    GameOver,
+}
+
+// 模拟状态：不含任何图片等资源句柄，可整体序列化做快照/恢复
+#[derive(Clone, Serialize, Deserialize)]
+pub struct World {
+    // 本地玩家，下标即输入槽位
+    pub players: Vec<Player>,
+    pub tiles: Vec<Rect>, // 平台块位置
+    // special_blocks now stores grid positions (col,row)
+    pub special_blocks: Vec<(usize, usize)>,
+    // coins: rect + its grid position (col,row)
+    pub coins: Vec<(Rect, (usize, usize))>,
+    pub score: i32,
+    // 已收集的金币数（与分数分开计）
+    pub coin_count: u32,
+    pub lives: u32,
+    pub power: Power,
+    // 本关已用时间（秒，暂停时不计）
+    pub level_time: f32,
+    // 关卡限时与剩余时间（秒），用完时失去一条命
+    pub time_limit: f32,
+    pub time_left: f32,
+    pub coin_spawn_timer: f32,
+    pub coin_spawn_interval: f32,
+    // 关卡区域大小（虚拟分辨率）
+    pub width: f32,
+    pub height: f32,
+    // level vertical offset used to compute grid rows
+    pub level_offset_y: f32,
+    // positions where coin has been collected; won't respawn there
+    pub consumed_coin_positions: Vec<(usize, usize)>,
+    // monsters (enemies)
+    pub monsters: Vec<Monster>,
+    // pipe (level goal)
+    pub pipe_rect: Option<Rect>,
+    // entering pipe state
+    pub entering_pipe: bool,
+    // 正在进入管道的玩家下标
+    pub enter_player: usize,
+    pub enter_timer: f32,
+    // 通关或失败后不再推进
+    pub outcome: Option<Outcome>,
+    // 旧快照中没有该字段，读取时视为全部关闭
+    #[serde(default)]
+    pub cheats: Cheats,
+}
+
+impl World {
+    pub fn new(level: &Level, width: f32, height: f32, players: usize, lives: u32, power: Power) -> Self {
+        // 使关卡底部对齐到窗口底部：计算整个关卡像素高度，然后从窗口高度减去它作为起始偏移
+        // 如果关卡高度比窗口高，offset_y 允许为负，从而保持原始布局
+        let map = &level.map;
+        let offset_y = height - map.len() as f32 * TILE_SIZE;
+
+        // 解析地图：'#' 是实心瓷砖，'P' 表示管道（同时作为实心瓷砖）
+        let mut tiles = Vec::new();
+        let mut pipe_rect = None;
+        for (row, line) in map.iter().enumerate() {
+            for (col, ch) in line.chars().enumerate() {
+                if ch == '#' || ch == 'P' {
+                    let y = offset_y + (row as f32) * TILE_SIZE;
+                    tiles.push(Rect::new(col as f32 * TILE_SIZE, y, TILE_SIZE, TILE_SIZE));
+                    if ch == 'P' {
+                        // 把管道放在该格子正上方
+                        let pipe_w = TILE_SIZE;
+                        let pipe_h = TILE_SIZE * 1.5;
+                        let px = col as f32 * TILE_SIZE + (TILE_SIZE - pipe_w) / 2.0;
+                        let py = y - pipe_h;
+                        pipe_rect = Some(Rect::new(px, py, pipe_w, pipe_h));
+                        // 为了让玩家可以站在管道顶部，添加一个薄的碰撞矩形
+                        let top_h = 8.0_f32;
+                        tiles.push(Rect::new(px, py, pipe_w, top_h));
+                    }
+                }
+            }
+        }
+
+        // 特殊方块同时加入 tiles（保证为实心方块）
+        let mut special_blocks = Vec::new();
+        for (col, row) in &level.special_blocks {
+            let y = offset_y + (*row as f32) * TILE_SIZE;
+            tiles.push(Rect::new(*col as f32 * TILE_SIZE, y, TILE_SIZE, TILE_SIZE));
+            special_blocks.push((*col, *row));
+        }
+
+        let ground_y = offset_y + (map.len() as f32 - 1.0) * TILE_SIZE - 1.0;
+        let ground_tiles: Vec<&Rect> = tiles.iter().filter(|t| t.y >= ground_y).collect();
+
+        // 地图没有指定管道 'P' 时，放在最右侧的地面块上
+        if pipe_rect.is_none()
+            && let Some(last) = ground_tiles.last()
+        {
+            let pipe_w = TILE_SIZE;
+            let pipe_h = TILE_SIZE * 1.5;
+            let px = last.x + (TILE_SIZE - pipe_w) / 2.0;
+            let py = last.y - pipe_h;
+            pipe_rect = Some(Rect::new(px, py, pipe_w, pipe_h));
+        }
+
+        // 在靠近地面的地方生成一个巡逻怪，范围放在地面的中间区域
+        let mut monsters = Vec::new();
+        if !ground_tiles.is_empty() {
+            let idx = ground_tiles.len() / 2;
+            let center_tile = ground_tiles[idx];
+            let center_x = center_tile.x + TILE_SIZE / 2.0;
+            let first_x = ground_tiles.first().unwrap().x;
+            let last_x = ground_tiles.last().unwrap().x + TILE_SIZE;
+            let mut range_min = center_x - TILE_SIZE * 3.0;
+            let mut range_max = center_x + TILE_SIZE * 3.0;
+            if range_min < first_x { range_min = first_x; }
+            if range_max > last_x { range_max = last_x; }
+            let mx = center_x - 12.0; // 居中放置怪物（宽 24）
+            let my = center_tile.y - 24.0;
+            monsters.push(Monster { x: mx, y: my, w: 24.0, h: 24.0, vx: 60.0, range_min, range_max });
+        }
+
+        Self {
+            players: (0..players).map(Player::spawn).collect(),
+            tiles,
+            special_blocks,
+            coins: Vec::new(),
+            score: 0,
+            coin_count: 0,
+            lives,
+            power,
+            level_time: 0.0,
+            time_limit: level.time_limit,
+            time_left: level.time_limit,
+            coin_spawn_timer: 0.0,
+            coin_spawn_interval: 0.5,
+            width,
+            height,
+            level_offset_y: offset_y,
+            consumed_coin_positions: Vec::new(),
+            monsters,
+            pipe_rect,
+            entering_pipe: false,
+            enter_player: 0,
+            enter_timer: 0.0,
+            outcome: None,
+            cheats: Cheats::default(),
+        }
+    }
+
+    // 快照为 JSON 文本，可用于快速存档、问题报告与测试用例
+    pub fn snapshot(&self) -> GameResult<String> {
+        serde_json::to_string_pretty(self).map_err(|e| GameError::CustomError(e.to_string()))
+    }
+
+    pub fn restore(text: &str) -> GameResult<Self> {
+        serde_json::from_str(text).map_err(|e| GameError::CustomError(e.to_string()))
+    }
+
+    // 状态哈希（对序列化结果做 FNV-1a），用于比对重放结果是否一致
+    pub fn state_hash(&self) -> u64 {
+        let bytes = serde_json::to_vec(self).unwrap_or_default();
+        bytes.iter().fold(0xcbf2_9ce4_8422_2325, |h, b| (h ^ *b as u64).wrapping_mul(0x0000_0100_0000_01b3))
+    }
+
+    // 手柄热插拔：按已连接的手柄数量增减本地玩家
+    pub fn set_player_count(&mut self, count: usize) {
+        while self.players.len() < count {
+            self.players.push(Player::spawn(self.players.len()));
+        }
+        self.players.truncate(count);
+    }
+
+    // 通关时剩余时间折算的奖励分数
+    pub fn time_bonus(&self) -> i32 {
+        self.time_left.max(0.0).ceil() as i32 * TIME_BONUS
+    }
+
+    // 玩家回到出生点，怪物回到巡逻起点，倒计时重新开始
+    pub fn respawn(&mut self) {
+        let count = self.players.len();
+        self.players = (0..count).map(Player::spawn).collect();
+        self.time_left = self.time_limit;
+        for m in &mut self.monsters {
+            m.vx = m.vx.abs();
+            // 将怪物放回 range_min
+            m.x = m.range_min + 4.0;
+        }
+    }
+
+    // 推进一步模拟；inputs 按玩家槽位排列，缺少的视为无输入
+    pub fn update(&mut self, dt: f32, inputs: &[PlayerInput]) -> Vec<Event> {
+        let mut events = Vec::new();
+        if self.outcome.is_some() {
+            return events;
+        }
+        self.level_time += dt;
+
+        // 若正在进入管道，则播放缓慢滑入动画并在结束后通关
+        if self.entering_pipe {
+            if let (Some(pipe), Some(player)) = (self.pipe_rect, self.players.get_mut(self.enter_player)) {
+                // 让玩家水平靠拢到管道中心并缓慢下滑
+                let target_x = pipe.x + pipe.w / 2.0 - player.w / 2.0;
+                // 平滑靠近
+                let dx = target_x - player.x;
+                player.x += dx * (0.1 + dt * 3.0);
+                // 缓慢下滑
+                player.y += 40.0 * dt;
+                self.enter_timer += dt;
+                if self.enter_timer > 1.2 || player.y > pipe.y + pipe.h * 0.5 {
+                    // 剩余时间折算为奖励分数（胜利界面上逐步累加显示）
+                    self.score += self.time_bonus();
+                    self.outcome = Some(Outcome::Victory);
+                    events.push(Event::Victory);
+                }
+                return events;
+            } else {
+                self.entering_pipe = false;
+            }
+        }
+
+        // 倒计时（进入管道后停止）
+        let before = self.time_left;
+        self.time_left = (self.time_left - dt).max(0.0);
+        if before > LOW_TIME && self.time_left <= LOW_TIME {
+            events.push(Event::TimeWarning);
+        }
+
+        // 地面随机刷新金币（周期性）
+        self.coin_spawn_timer += dt;
+        if self.coin_spawn_timer >= self.coin_spawn_interval {
+            self.coin_spawn_timer = 0.0;
+            // 找到底部的 tiles（y 接近窗口底部）
+            let ground_tiles: Vec<&Rect> = self.tiles.iter().filter(|t| t.y >= self.height - TILE_SIZE - 1.0).collect();
+            if !ground_tiles.is_empty() {
+                // 选择中间的一个地面块刷金币，避免引入 rand 依赖
+                let idx = ground_tiles.len() / 2;
+                let t = ground_tiles[idx];
+                let coin_x = t.x + (TILE_SIZE - COIN_SIZE) / 2.0;
+                let coin_y = t.y - COIN_SIZE - 2.0;
+                // 计算格子坐标
+                let col = (t.x / TILE_SIZE) as usize;
+                let row = ((t.y - self.level_offset_y) / TILE_SIZE) as usize;
+                let exists = self.coins.iter().any(|(c, _)| (c.x - coin_x).abs() < 0.1 && (c.y - coin_y).abs() < 0.1);
+                let consumed = self.consumed_coin_positions.iter().any(|(cc, rr)| *cc == col && *rr == row);
+                if !exists && !consumed {
+                    self.coins.push((Rect::new(coin_x, coin_y, COIN_SIZE, COIN_SIZE), (col, row)));
+                }
+            }
+        }
+
+        let (w, h) = (self.width, self.height);
+        for (slot, player) in self.players.iter_mut().enumerate() {
+            let input = inputs.get(slot).copied().unwrap_or_default();
+            if self.cheats.noclip {
+                // 穿墙飞行：跳跃键上升、跑步键下降，不受重力与方块影响
+                player.vx = input.move_x * RUN_SPEED;
+                player.vy = if input.jump { -RUN_SPEED } else if input.run { RUN_SPEED } else { 0.0 };
+                player.x = (player.x + player.vx * dt).clamp(0.0, w - player.w);
+                player.y = (player.y + player.vy * dt).min(h - player.h);
+                player.on_ground = false;
+                continue;
+            }
+            if input.jump && player.on_ground {
+                player.vy = JUMP_V;
+                player.on_ground = false;
+                events.push(Event::Jump);
+            }
+
+            // 水平速度（按住跑步键时加速）
+            let speed = if input.run { RUN_SPEED } else { MOVE_SPEED };
+            let prev_vx = player.vx;
+            player.vx = input.move_x * speed;
+            if player.on_ground && prev_vx * player.vx < 0.0 && prev_vx.abs() > MOVE_SPEED * 0.9 {
+                events.push(Event::Skid { x: player.x + player.w / 2.0, y: player.y + player.h });
+            }
+
+            // 应用重力
+            player.vy += GRAVITY * dt;
+
+            // 先移动水平并检测水平碰撞；左右边界先于方块限制，
+            // 否则出界的玩家会越过边上的方块下落，被推回画面时嵌进方块里
+            player.x = (player.x + player.vx * dt).clamp(0.0, w - player.w);
+            let mut prect = player.rect();
+            for tile in &self.tiles {
+                if rect_intersect(&prect, tile) {
+                    if player.vx > 0.0 {
+                        player.x = tile.x - player.w;
+                    } else if player.vx < 0.0 {
+                        player.x = tile.x + tile.w;
+                    }
+                    player.vx = 0.0;
+                    prect = player.rect();
+                }
+            }
+
+            // 然后移动垂直并检测垂直碰撞
+            let was_on_ground = player.on_ground;
+            let fall_vy = player.vy;
+            player.y += player.vy * dt;
+            prect = player.rect();
+            player.on_ground = false;
+            for tile in &self.tiles {
+                if rect_intersect(&prect, tile) {
+                    if player.vy > 0.0 {
+                        player.y = tile.y - player.h;
+                        player.vy = 0.0;
+                        player.on_ground = true;
+                    } else if player.vy < 0.0 {
+                        // 从下面顶到方块的处理：若是特殊方块，生成金币
+                        player.y = tile.y + tile.h;
+                        events.push(Event::Bump);
+                        // 检查是否为特殊方块（比较格子坐标）
+                        let col = (tile.x / TILE_SIZE) as usize;
+                        let row = ((tile.y - self.level_offset_y) / TILE_SIZE) as usize;
+                        let is_special = self.special_blocks.iter().any(|(sc, sr)| *sc == col && *sr == row);
+                        if is_special {
+                            let coin_x = tile.x + (TILE_SIZE - COIN_SIZE) / 2.0;
+                            let coin_y = tile.y - COIN_SIZE - 2.0;
+                            // 只有当该位置没有金币且未被消耗时才生成
+                            let exists = self.coins.iter().any(|(c, _)| (c.x - coin_x).abs() < 0.1 && (c.y - coin_y).abs() < 0.1);
+                            let consumed = self.consumed_coin_positions.iter().any(|(cc, rr)| *cc == col && *rr == row);
+                            if !exists && !consumed {
+                                self.coins.push((Rect::new(coin_x, coin_y, COIN_SIZE, COIN_SIZE), (col, row)));
+                            }
+                            // 把这个特殊方块变回普通瓷块（从 special_blocks 中移除）
+                            self.special_blocks.retain(|(sc, sr)| !(*sc == col && *sr == row));
+                            self.score += BLOCK_POINTS;
+                            events.push(Event::BlockOpened { x: tile.x + TILE_SIZE / 2.0, y: tile.y + TILE_SIZE / 2.0 });
+                            events.push(Event::Points { x: tile.x + TILE_SIZE / 2.0, y: tile.y, points: BLOCK_POINTS });
+                        }
+                        player.vy = 0.0;
+                    }
+                    prect = player.rect();
+                }
+            }
+
+            if player.on_ground && !was_on_ground && fall_vy > LAND_DUST_VY {
+                events.push(Event::Land { x: player.x + player.w / 2.0, y: player.y + player.h });
+            }
+
+            // 限制在关卡区域内（简单处理）
+            if player.y + player.h > h {
+                player.y = h - player.h;
+                player.vy = 0.0;
+                player.on_ground = true;
+            }
+
+            // 检测玩家是否真正站在管道顶部（水平中心在管道范围内且玩家底部与管道顶对齐）以触发进入管道
+            if let Some(pipe) = self.pipe_rect {
+                let px_center = player.x + player.w / 2.0;
+                if px_center >= pipe.x && px_center <= pipe.x + pipe.w {
+                    let player_bottom = player.y + player.h;
+                    // 仅在玩家底部与管道顶接近（容差）且被判定为着地时才触发进入
+                    let tol = 6.0_f32;
+                    if (player_bottom - pipe.y).abs() <= tol && player.on_ground && !self.entering_pipe {
+                        // 开始进入管道动画
+                        self.entering_pipe = true;
+                        self.enter_player = slot;
+                        self.enter_timer = 0.0;
+                        // 锁定横向移动
+                        player.vx = 0.0;
+                        events.push(Event::PipeEnter);
+                    }
+                }
+            }
+        }
+
+        // 拾取金币检测：任一玩家与金币相交则得分并移除金币
+        self.coins.retain(|(coin_rect, grid)| {
+            if self.players.iter().any(|p| rect_intersect(&p.rect(), coin_rect)) {
+                self.score += COIN_POINTS;
+                self.coin_count += 1;
+                events.push(Event::Coin { x: coin_rect.x + COIN_SIZE / 2.0, y: coin_rect.y + COIN_SIZE / 2.0 });
+                events.push(Event::Points { x: coin_rect.x + COIN_SIZE / 2.0, y: coin_rect.y, points: COIN_POINTS });
+                if self.coin_count.is_multiple_of(COINS_PER_LIFE) {
+                    self.lives += 1;
+                    events.push(Event::ExtraLife { x: coin_rect.x + COIN_SIZE / 2.0, y: coin_rect.y - 16.0 });
+                }
+                // 触发下一周期立即刷新的机会：把计时器设为间隔
+                self.coin_spawn_timer = self.coin_spawn_interval;
+                // 记录该格子已被消耗，未来不再刷新
+                self.consumed_coin_positions.push(*grid);
+                false
+            } else {
+                true
+            }
+        });
+
+        // 更新怪物巡逻与与玩家碰撞检测
+        let mut stomped = Vec::new();
+        // 时间用完同样失去一条命
+        let mut died = self.time_left <= 0.0 && !self.cheats.god;
+        for (i, m) in self.monsters.iter_mut().enumerate() {
+            // 移动
+            m.x += m.vx * dt;
+            if m.x < m.range_min {
+                m.x = m.range_min;
+                m.vx = m.vx.abs();
+            } else if m.x + m.w > m.range_max {
+                m.x = m.range_max - m.w;
+                m.vx = -m.vx.abs();
+            }
+            // 简单重力作用 (保持在 tile 上方)
+            // 检查是否站在某个 tile 上
+            let mut on_tile = false;
+            for tile in &self.tiles {
+                let mut mrect = m.rect();
+                mrect.y += 1.0; // 向下检测
+                if rect_intersect(&mrect, tile) {
+                    on_tile = true;
+                    // 将怪物固定在地面上
+                    m.y = tile.y - m.h;
+                    break;
+                }
+            }
+            if !on_tile {
+                // 自由落体
+                m.y += GRAVITY * dt;
+            }
+            // 玩家从上方落到怪物身上 -> 踩扁怪物并弹起；其他方向碰到 -> 失去一条命
+            for p in &mut self.players {
+                if !rect_intersect(&p.rect(), &m.rect()) {
+                    continue;
+                }
+                if is_stomp(p, m, dt) {
+                    p.vy = STOMP_BOUNCE;
+                    stomped.push(i);
+                    break;
+                }
+                died |= !self.cheats.god;
+            }
+        }
+        for i in stomped.into_iter().rev() {
+            let m = self.monsters.remove(i);
+            self.score += STOMP_POINTS;
+            events.push(Event::Stomp { x: m.x + m.w / 2.0, y: m.y + m.h / 2.0 });
+            events.push(Event::Points { x: m.x + m.w / 2.0, y: m.y, points: STOMP_POINTS });
+        }
+        // 还有剩余生命时失去一条命并在起点重生，否则游戏结束
+        if died {
+            self.lives = self.lives.saturating_sub(1);
+            self.power = Power::Small;
+            if self.lives > 0 {
+                self.respawn();
+                events.push(Event::Died);
+            } else {
+                self.outcome = Some(Outcome::GameOver);
+                events.push(Event::GameOver);
+            }
+        }
+        events
+    }
+}
+
+// 玩家从上方落到怪物身上：正在下落，且上一步玩家底部还在怪物顶部之上
+fn is_stomp(player: &Player, monster: &Monster, dt: f32) -> bool {
+    player.vy > 0.0 && player.y + player.h - player.vy * dt <= monster.y + STOMP_TOLERANCE
+}
+
+// 简单 AABB 碰撞检测
+pub fn rect_intersect(a: &Rect, b: &Rect) -> bool {
+    a.x < b.x + b.w && a.x + a.w > b.x && a.y < b.y + b.h && a.y + a.h > b.y
+}
@@ -0,0 +1,16 @@
+// 提交的图集清单必须与 resources/ 中的精灵 PNG 一致，改了图片后需要重新运行 `cargo run --bin atlas`
+use std::fs;
+use std::path::Path;
+
+use game::atlas::{self, Manifest, SPRITES};
+
+#[test]
+fn committed_atlas_matches_sprites() {
+    let resources = Path::new(env!("CARGO_MANIFEST_DIR")).join("resources");
+    let manifest: Manifest = toml::from_str(&fs::read_to_string(resources.join("atlas.toml")).unwrap()).unwrap();
+    for (name, file) in SPRITES {
+        let region = manifest.regions.get(name).unwrap_or_else(|| panic!("atlas has no region for {}", name));
+        let bytes = fs::read(resources.join(file)).unwrap();
+        assert_eq!(region.hash, atlas::hash(&bytes), "atlas is out of date for {}", file);
+    }
+}
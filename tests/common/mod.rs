@@ -0,0 +1,124 @@
+// 无窗口的玩法测试工具：由字符串构建关卡，按固定步长输入脚本推进模拟，
+// 并像游戏本体一样把通关/失败事件交给界面栈
+// 各测试文件只用到其中一部分
+#![allow(dead_code)]
+
+use game::level::Level;
+use game::replay::{self, FIXED_DT};
+use game::save::{Power, START_LIVES};
+use game::scene::{Scenes, Screen};
+use game::world::{Event, Player, PlayerInput, World, TILE_SIZE, VIRTUAL_H, VIRTUAL_W};
+
+pub const IDLE: PlayerInput = PlayerInput { move_x: 0.0, jump: false, run: false, fire: false };
+pub const RIGHT: PlayerInput = PlayerInput { move_x: 1.0, jump: false, run: false, fire: false };
+pub const LEFT: PlayerInput = PlayerInput { move_x: -1.0, jump: false, run: false, fire: false };
+pub const JUMP: PlayerInput = PlayerInput { move_x: 0.0, jump: true, run: false, fire: false };
+pub const FIRE: PlayerInput = PlayerInput { move_x: 0.0, jump: false, run: false, fire: true };
+
+// 地图字符串：'#' 瓷砖、'P' 管道、'?' 特殊方块、'.' 空格；首尾空白忽略，底部对齐到画面底部
+pub fn level(map: &str) -> Level {
+    let mut special_blocks = Vec::new();
+    let rows = map
+        .trim()
+        .lines()
+        .enumerate()
+        .map(|(row, line)| {
+            line.trim()
+                .chars()
+                .enumerate()
+                .map(|(col, ch)| {
+                    if ch == '?' {
+                        special_blocks.push((col, row));
+                        '.'
+                    } else {
+                        ch
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    Level { id: "test".to_string(), name: "Test".to_string(), time_limit: 100.0, map: rows, special_blocks, next: None }
+}
+
+pub struct Harness {
+    pub world: World,
+    pub scenes: Scenes,
+    // 到目前为止产生的全部事件
+    pub events: Vec<Event>,
+    pub steps: usize,
+}
+
+impl Harness {
+    pub fn new(map: &str) -> Self {
+        Self::with_level(&level(map))
+    }
+
+    pub fn with_level(level: &Level) -> Self {
+        Self {
+            world: World::new(level, VIRTUAL_W, VIRTUAL_H, 1, START_LIVES, Power::Small),
+            scenes: Scenes::new(Screen::Playing),
+            events: Vec::new(),
+            steps: 0,
+        }
+    }
+
+    // 去掉关卡自动生成的巡逻怪，避免干扰与怪物无关的测试
+    pub fn without_monsters(mut self) -> Self {
+        self.world.monsters.clear();
+        self
+    }
+
+    pub fn player(&self) -> &Player {
+        &self.world.players[0]
+    }
+
+    // 把玩家放到第 col 列、站在第 row 行的方块上
+    pub fn stand_on(&mut self, col: usize, row: usize) {
+        let top = self.world.level_offset_y + row as f32 * TILE_SIZE;
+        let player = &mut self.world.players[0];
+        player.x = col as f32 * TILE_SIZE + (TILE_SIZE - player.w) / 2.0;
+        player.y = top - player.h;
+        player.vx = 0.0;
+        player.vy = 0.0;
+        player.on_ground = true;
+    }
+
+    // 推进一个固定步；通关/失败时切换界面，界面过渡与游戏中一样随时间推进
+    pub fn step(&mut self, input: PlayerInput) {
+        let events = replay::step(&mut self.world, &[input]);
+        if events.iter().any(|e| matches!(e, Event::Victory | Event::GameOver)) {
+            self.scenes.finish(self.world.outcome.expect("outcome is set with its event"));
+        }
+        self.events.extend(events);
+        self.scenes.update(FIXED_DT);
+        self.steps += 1;
+    }
+
+    pub fn run(&mut self, steps: usize, input: PlayerInput) {
+        for _ in 0..steps {
+            self.step(input);
+        }
+    }
+
+    // 输入脚本：依次按每段的输入推进若干步
+    pub fn script(&mut self, script: &[(usize, PlayerInput)]) {
+        for &(steps, input) in script {
+            self.run(steps, input);
+        }
+    }
+
+    // 推进直到满足条件，最多 max_steps 步；返回是否满足
+    pub fn run_until(&mut self, max_steps: usize, input: PlayerInput, done: impl Fn(&Harness) -> bool) -> bool {
+        for _ in 0..max_steps {
+            if done(self) {
+                return true;
+            }
+            self.step(input);
+        }
+        done(self)
+    }
+
+    pub fn count(&self, matches: impl Fn(&Event) -> bool) -> usize {
+        self.events.iter().filter(|e| matches(e)).count()
+    }
+}
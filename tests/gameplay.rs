@@ -0,0 +1,274 @@
+mod common;
+
+use common::{Harness, FIRE, IDLE, JUMP, RIGHT};
+use game::save::Power;
+use game::scene::Screen;
+use game::world::{Event, Outcome, BLOCK_POINTS, COINS_PER_LIFE, COIN_POINTS, COIN_SIZE, STOMP_POINTS};
+use ggez::graphics::Rect;
+
+// 三行高的平地，底部一行是地面（y = 368）
+const FLAT: &str = "
+.........................
+.........................
+#########################
+";
+
+// 同上，第 5 列的地面格是管道
+const PIPE: &str = "
+.........................
+.........................
+#####P###################
+";
+
+// 第 8 列上方有一个特殊方块，底部距地面上的玩家头顶 2 像素
+const SPECIAL: &str = "
+........?................
+.........................
+#########################
+";
+
+const GROUND_Y: f32 = 368.0;
+
+#[test]
+fn player_falls_and_lands_on_ground() {
+    let mut h = Harness::new(FLAT).without_monsters();
+    h.run(120, IDLE);
+    assert!(h.player().on_ground);
+    assert_eq!(h.player().y + h.player().h, GROUND_Y);
+    assert_eq!(h.player().x, 50.0);
+}
+
+#[test]
+fn walking_moves_at_move_speed() {
+    let mut h = Harness::new(FLAT).without_monsters();
+    h.stand_on(1, 2);
+    let start = h.player().x;
+    h.run(60, RIGHT);
+    assert!((h.player().x - (start + 200.0)).abs() < 0.01, "x = {}", h.player().x);
+    assert!(h.player().on_ground);
+}
+
+// 与最初的版本一致：着地时按住跳跃就会起跳，按住不放连续跳
+#[test]
+fn holding_jump_keeps_jumping() {
+    let mut h = Harness::new(FLAT).without_monsters();
+    h.stand_on(1, 2);
+    h.run(120, JUMP);
+    assert!(h.count(|e| *e == Event::Jump) > 1);
+}
+
+#[test]
+fn landing_on_pipe_enters_it_and_wins() {
+    let mut h = Harness::new(PIPE).without_monsters();
+    let pipe = h.world.pipe_rect.expect("map has a pipe");
+    h.world.players[0].x = pipe.x + (pipe.w - h.player().w) / 2.0;
+    h.world.players[0].y = pipe.y - 60.0;
+    assert!(h.run_until(300, IDLE, |h| h.world.outcome.is_some()));
+    assert_eq!(h.count(|e| *e == Event::PipeEnter), 1);
+    assert_eq!(h.world.outcome, Some(Outcome::Victory));
+    // 剩余时间折算的奖励计入分数
+    assert_eq!(h.world.score, h.world.time_bonus());
+    // 胜利界面在过渡中点压到游戏画面之上
+    h.run(30, IDLE);
+    assert_eq!(h.scenes.top(), Screen::Victory);
+    assert_eq!(h.scenes.visible(), &[Screen::Playing, Screen::Victory]);
+}
+
+#[test]
+fn walking_through_pipe_base_does_not_enter_it() {
+    let mut h = Harness::new(PIPE).without_monsters();
+    h.stand_on(2, 2);
+    h.run(60, RIGHT);
+    let pipe = h.world.pipe_rect.unwrap();
+    assert!(h.player().x > pipe.x + pipe.w, "player should have walked past the pipe");
+    assert_eq!(h.count(|e| *e == Event::PipeEnter), 0);
+    assert!(!h.world.entering_pipe);
+    assert_eq!(h.world.outcome, None);
+}
+
+#[test]
+fn standing_on_pipe_lip_off_center_does_not_enter_it() {
+    let mut h = Harness::new(PIPE).without_monsters();
+    let pipe = h.world.pipe_rect.unwrap();
+    // 玩家中心在管道右侧之外，但身体仍搭在管道顶上
+    h.world.players[0].x = pipe.x + pipe.w - 8.0;
+    h.world.players[0].y = pipe.y - 60.0;
+    h.run(60, IDLE);
+    assert!(h.player().on_ground);
+    assert_eq!(h.player().y + h.player().h, pipe.y);
+    assert_eq!(h.count(|e| *e == Event::PipeEnter), 0);
+    assert_eq!(h.scenes.top(), Screen::Playing);
+}
+
+#[test]
+fn bumping_special_block_spawns_one_coin() {
+    let mut h = Harness::new(SPECIAL).without_monsters();
+    assert_eq!(h.world.special_blocks, vec![(8, 0)]);
+    h.stand_on(8, 2);
+    h.script(&[(5, JUMP), (30, IDLE)]);
+    assert_eq!(h.count(|e| *e == Event::Bump), 1);
+    assert_eq!(h.count(|e| matches!(e, Event::BlockOpened { .. })), 1);
+    assert!(h.world.special_blocks.is_empty());
+    assert_eq!(h.world.score, BLOCK_POINTS);
+    let coins = h.world.coins.iter().filter(|(_, grid)| *grid == (8, 0)).count();
+    assert_eq!(coins, 1);
+
+    // 再次顶到时已是普通方块：不再出金币、不再加分
+    h.script(&[(5, JUMP), (30, IDLE)]);
+    assert_eq!(h.count(|e| *e == Event::Bump), 2);
+    assert_eq!(h.count(|e| matches!(e, Event::BlockOpened { .. })), 1);
+    assert_eq!(h.world.score, BLOCK_POINTS);
+    assert_eq!(h.world.coins.iter().filter(|(_, grid)| *grid == (8, 0)).count(), 1);
+}
+
+#[test]
+fn collecting_block_coin_scores_and_consumes_it() {
+    let mut h = Harness::new(SPECIAL).without_monsters();
+    h.stand_on(8, 2);
+    h.script(&[(5, JUMP), (30, IDLE)]);
+    let (coin, _) = *h.world.coins.iter().find(|(_, grid)| *grid == (8, 0)).expect("block coin");
+    // 直接把玩家放到金币上
+    h.world.players[0].x = coin.x;
+    h.world.players[0].y = coin.y;
+    h.step(IDLE);
+    assert_eq!(h.count(|e| matches!(e, Event::Coin { .. })), 1);
+    assert_eq!(h.world.score, BLOCK_POINTS + COIN_POINTS);
+    assert_eq!(h.world.coin_count, 1);
+    assert!(h.world.consumed_coin_positions.contains(&(8, 0)));
+    assert!(!h.world.coins.iter().any(|(_, grid)| *grid == (8, 0)));
+}
+
+// 在玩家身上放一枚金币并拾取
+fn collect_coin(h: &mut Harness, grid: (usize, usize)) {
+    let p = h.player().rect();
+    h.world.coins.push((Rect::new(p.x, p.y, COIN_SIZE, COIN_SIZE), grid));
+    h.step(IDLE);
+}
+
+#[test]
+fn every_hundredth_coin_awards_an_extra_life() {
+    let mut h = Harness::new(FLAT).without_monsters();
+    h.stand_on(2, 2);
+    h.world.coin_count = COINS_PER_LIFE - 2;
+    collect_coin(&mut h, (20, 0));
+    assert_eq!(h.world.lives, 3);
+    collect_coin(&mut h, (21, 0));
+    assert_eq!(h.world.coin_count, COINS_PER_LIFE);
+    assert_eq!(h.world.lives, 4);
+    assert_eq!(h.count(|e| matches!(e, Event::ExtraLife { .. })), 1);
+    // 额外的命不计分
+    assert_eq!(h.world.score, 2 * COIN_POINTS);
+    collect_coin(&mut h, (22, 0));
+    assert_eq!(h.world.lives, 4);
+}
+
+#[test]
+fn bumping_plain_block_scores_nothing() {
+    let mut h = Harness::new(SPECIAL).without_monsters();
+    h.stand_on(6, 2);
+    h.world.tiles.push(Rect::new(6.0 * 32.0, h.world.level_offset_y, 32.0, 32.0));
+    h.script(&[(5, JUMP), (30, IDLE)]);
+    assert_eq!(h.count(|e| *e == Event::Bump), 1);
+    assert_eq!(h.world.score, 0);
+    assert_eq!(h.count(|e| matches!(e, Event::Points { .. })), 0);
+}
+
+// 地面中间自动生成的巡逻怪
+fn monster_x(h: &Harness) -> f32 {
+    h.world.monsters[0].x
+}
+
+#[test]
+fn walking_into_monster_costs_a_life_and_respawns() {
+    let mut h = Harness::new(FLAT);
+    assert_eq!(h.world.monsters.len(), 1);
+    let col = (monster_x(&h) / 32.0) as usize - 3;
+    h.stand_on(col, 2);
+    assert!(h.run_until(120, RIGHT, |h| h.count(|e| *e == Event::Died) > 0));
+    assert_eq!(h.world.lives, 2);
+    // 回到出生点，倒计时重新开始
+    assert_eq!((h.player().x, h.player().y), (50.0, 50.0));
+    assert_eq!(h.world.time_left, h.world.time_limit);
+    assert_eq!(h.world.monsters.len(), 1);
+    assert_eq!(h.scenes.top(), Screen::Playing);
+}
+
+#[test]
+fn stomping_monster_removes_it_and_bounces() {
+    let mut h = Harness::new(FLAT);
+    let m = h.world.monsters[0].clone();
+    h.world.players[0].x = m.x;
+    h.world.players[0].y = m.y - 60.0;
+    assert!(h.run_until(120, IDLE, |h| h.world.monsters.is_empty()));
+    assert_eq!(h.count(|e| matches!(e, Event::Stomp { .. })), 1);
+    assert_eq!(h.count(|e| matches!(e, Event::Points { points: STOMP_POINTS, .. })), 1);
+    assert!(h.player().vy < 0.0, "player should bounce off the monster");
+    assert_eq!(h.world.lives, 3);
+    assert_eq!(h.count(|e| *e == Event::Died), 0);
+}
+
+#[test]
+fn falling_onto_monster_side_still_costs_a_life() {
+    let mut h = Harness::new(FLAT);
+    let m = h.world.monsters[0].clone();
+    // 下落中从侧面撞上：脚已低于怪物顶部，不算踩
+    let player = &mut h.world.players[0];
+    player.x = m.x - player.w + 2.0;
+    player.y = m.y + 12.0 - player.h;
+    player.vy = 100.0;
+    h.step(IDLE);
+    assert_eq!(h.count(|e| *e == Event::Died), 1);
+    assert_eq!(h.count(|e| matches!(e, Event::Stomp { .. })), 0);
+    assert_eq!(h.world.monsters.len(), 1);
+    assert_eq!(h.world.score, 0);
+}
+
+#[test]
+fn stomping_scores_once_per_monster() {
+    let mut h = Harness::new(FLAT);
+    let m = h.world.monsters[0].clone();
+    h.world.players[0].x = m.x;
+    h.world.players[0].y = m.y - 60.0;
+    h.run(120, IDLE);
+    assert_eq!(h.count(|e| matches!(e, Event::Stomp { .. })), 1);
+    // 地面中间刷出的金币也会被捡到，只看踩怪的得分
+    assert_eq!(h.count(|e| matches!(e, Event::Points { points: STOMP_POINTS, .. })), 1);
+    assert!(h.player().on_ground);
+}
+
+#[test]
+fn last_life_lost_to_monster_is_game_over() {
+    let mut h = Harness::new(FLAT);
+    h.world.lives = 1;
+    let col = (monster_x(&h) / 32.0) as usize - 3;
+    h.stand_on(col, 2);
+    assert!(h.run_until(120, RIGHT, |h| h.world.outcome.is_some()));
+    assert_eq!(h.world.outcome, Some(Outcome::GameOver));
+    assert_eq!(h.count(|e| *e == Event::GameOver), 1);
+    // 失败界面替换游戏画面
+    h.run(30, IDLE);
+    assert_eq!(h.scenes.visible(), &[Screen::GameOver]);
+}
+
+#[test]
+fn god_mode_ignores_monster_contact() {
+    let mut h = Harness::new(FLAT);
+    h.world.cheats.god = true;
+    let col = (monster_x(&h) / 32.0) as usize - 3;
+    h.stand_on(col, 2);
+    h.run(120, RIGHT);
+    assert_eq!(h.count(|e| *e == Event::Died), 0);
+    assert_eq!(h.world.lives, 3);
+}
+
+// Fire 只是读进输入，还没有对应的玩法：模拟结果与不按时完全相同
+#[test]
+fn fire_does_not_change_the_simulation() {
+    let mut idle = Harness::new(FLAT);
+    let mut fire = Harness::new(FLAT);
+    fire.world.power = Power::Fire;
+    idle.world.power = Power::Fire;
+    idle.run(120, IDLE);
+    fire.run(120, FIRE);
+    assert_eq!(idle.world.state_hash(), fire.world.state_hash());
+}
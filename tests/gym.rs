@@ -0,0 +1,159 @@
+mod common;
+
+use std::time::Instant;
+
+use game::gym::{Action, Cell, Config, Env};
+use game::replay::FIXED_DT;
+use game::world::{Event, Outcome};
+
+// 三行高的平地，管道自动放在最右侧的地面上；怪物在地面中间巡逻
+const FLAT: &str = "
+.........................
+.........................
+#########################
+";
+
+// 管道在第 1 列，玩家出生后直接落在管道上
+const PIPE_AT_SPAWN: &str = "
+.........................
+.........................
+#P#######################
+";
+
+const SPECIAL: &str = "
+........?................
+.........................
+#########################
+";
+
+// 不随机空跑，开局与关卡完全一致
+fn config() -> Config {
+    Config { random_start_steps: 0, ..Config::default() }
+}
+
+#[test]
+fn observation_is_centered_on_player() {
+    let level = common::level(FLAT);
+    let mut env = Env::new(config(), &level);
+    for _ in 0..60 {
+        env.step(Action::Idle);
+    }
+    let obs = env.observe();
+    let size = obs.view_size;
+    assert_eq!(size, 13);
+    assert_eq!(obs.tiles.len(), size * size);
+    let center = size / 2 * size + size / 2;
+    assert!(obs.on_ground);
+    assert_eq!(obs.tiles[center], Cell::Empty);
+    // 脚下一格是地面
+    assert_eq!(obs.tiles[center + size], Cell::Solid);
+    assert_eq!(obs.monsters.len(), 1);
+    assert!(obs.monsters[0][0] > 0.0, "monster is to the right of the player");
+    assert!(obs.pipe.is_some_and(|[dx, _]| dx > 0.0));
+}
+
+#[test]
+fn observation_marks_special_blocks_and_pipes() {
+    let obs = Env::new(config(), &common::level(SPECIAL)).observe();
+    assert!(!obs.tiles.contains(&Cell::Special), "block at col 8 is outside the view at spawn");
+    let mut env = Env::new(Config { view_radius: 8, ..config() }, &common::level(SPECIAL));
+    assert!(env.observe().tiles.contains(&Cell::Special));
+    let obs = env.reset(0, &common::level(PIPE_AT_SPAWN));
+    assert!(obs.tiles.contains(&Cell::Pipe));
+}
+
+#[test]
+fn features_have_fixed_length() {
+    let env = Env::new(config(), &common::level(FLAT));
+    let obs = env.observe();
+    let k = 3;
+    let expected = obs.view_size * obs.view_size + 3 + 4 * k + 3 * k + 3 + 1;
+    assert_eq!(obs.features(k).len(), expected);
+    assert_eq!(obs.features(0).len(), expected - 7 * k);
+}
+
+#[test]
+fn same_seed_replays_same_episode() {
+    let level = common::level(FLAT);
+    let actions = [Action::Right, Action::RunJumpRight, Action::Idle, Action::Left, Action::JumpLeft];
+    let run = |seed| {
+        let mut env = Env::new(Config::default(), &level);
+        env.reset(seed, &level);
+        for i in 0..200 {
+            env.step(actions[i % actions.len()]);
+        }
+        env.world().state_hash()
+    };
+    assert_eq!(run(7), run(7));
+    // 不同种子的随机空跑步数不同
+    let starts: Vec<f32> = (0..8)
+        .map(|seed| {
+            let mut env = Env::new(Config::default(), &level);
+            env.reset(seed, &level);
+            env.world().level_time
+        })
+        .collect();
+    assert!(starts.iter().any(|t| *t != starts[0]), "starts: {:?}", starts);
+}
+
+#[test]
+fn moving_toward_pipe_is_rewarded() {
+    let level = common::level(FLAT);
+    let mut env = Env::new(config(), &level);
+    for _ in 0..60 {
+        env.step(Action::Idle);
+    }
+    assert!(env.step(Action::Right).reward > 0.0);
+    env.step(Action::Idle);
+    assert!(env.step(Action::Left).reward < 0.0);
+}
+
+#[test]
+fn death_ends_episode_with_penalty() {
+    let level = common::level(FLAT);
+    let mut env = Env::new(config(), &level);
+    let last = (0..600).map(|_| env.step(Action::RunRight)).find(|step| step.done).expect("episode should end");
+    assert!(last.events.contains(&Event::Died));
+    assert!(last.reward <= -env.config.rewards.death + 0.1, "reward {}", last.reward);
+    assert_eq!(env.world().lives, 2);
+}
+
+#[test]
+fn entering_pipe_wins_with_bonus() {
+    let level = common::level(PIPE_AT_SPAWN);
+    let mut env = Env::new(config(), &level);
+    let last = (0..600).map(|_| env.step(Action::Idle)).find(|step| step.done).expect("episode should end");
+    assert_eq!(env.world().outcome, Some(Outcome::Victory));
+    assert!(last.events.contains(&Event::Victory));
+    assert!(last.reward >= env.config.rewards.victory, "reward {}", last.reward);
+}
+
+#[test]
+fn max_steps_and_frame_skip_end_episode() {
+    let level = common::level(FLAT);
+    let mut env = Env::new(Config { frame_skip: 4, max_steps: Some(40), ..config() }, &level);
+    let steps = (1..).find(|_| env.step(Action::Idle).done).unwrap();
+    assert_eq!(steps, 10);
+    assert_eq!(env.steps(), 40);
+}
+
+#[test]
+fn runs_much_faster_than_real_time() {
+    let level = common::level(FLAT);
+    let mut env = Env::new(Config::default(), &level);
+    let started = Instant::now();
+    let mut simulated = 0.0;
+    let mut seed = 1u64;
+    for _ in 0..20_000 {
+        seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        let action = Action::ALL[(seed >> 33) as usize % Action::ALL.len()];
+        if env.step(action).done {
+            simulated += env.steps() as f32 * FIXED_DT;
+            env.reset(seed, &level);
+        }
+    }
+    simulated += env.steps() as f32 * FIXED_DT;
+    let wall = started.elapsed().as_secs_f32();
+    // 调试构建下也应远快于实时
+    assert!(simulated > wall * 10.0, "simulated {:.1}s in {:.2}s", simulated, wall);
+}
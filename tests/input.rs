@@ -0,0 +1,63 @@
+use game::input::{Action, Bindings, Input};
+use ggez::input::keyboard::KeyCode;
+
+// 按帧给出按下的键，检查每帧的按下 / 刚按下 / 刚松开状态
+#[test]
+fn tracks_press_and_release_edges() {
+    let mut input = Input::new(Bindings::default());
+    let frames: [&[KeyCode]; 5] = [&[], &[KeyCode::Space], &[KeyCode::Space], &[], &[]];
+    let expected = [(false, false, false), (true, true, false), (true, false, false), (false, false, true), (false, false, false)];
+    for (keys, (pressed, just_pressed, just_released)) in frames.iter().zip(expected) {
+        input.refresh(|k| keys.contains(&k));
+        assert_eq!(input.pressed(0, Action::Jump), pressed, "keys {:?}", keys);
+        assert_eq!(input.just_pressed(0, Action::Jump), just_pressed, "keys {:?}", keys);
+        assert_eq!(input.just_released(0, Action::Jump), just_released, "keys {:?}", keys);
+    }
+}
+
+// 同一动作的两个键交替按住时不算松开
+#[test]
+fn switching_keys_of_one_action_is_not_a_release() {
+    let mut input = Input::new(Bindings::default());
+    input.refresh(|k| k == KeyCode::Left);
+    input.refresh(|k| k == KeyCode::A);
+    assert!(input.pressed(0, Action::MoveLeft));
+    assert!(!input.just_pressed(0, Action::MoveLeft));
+    assert!(!input.just_released(0, Action::MoveLeft));
+    assert_eq!(input.move_x(0), -1.0);
+}
+
+// 键盘只属于槽位 0
+#[test]
+fn keyboard_drives_only_the_first_slot() {
+    let mut input = Input::new(Bindings::default());
+    input.refresh(|k| k == KeyCode::Space);
+    assert!(input.pressed(0, Action::Jump));
+    assert!(!input.pressed(1, Action::Jump));
+    assert_eq!(input.player_count(), 1);
+}
+
+// 改绑只替换选中的那个按键，其余默认按键保留；超出列表时追加
+#[test]
+fn rebinding_replaces_only_the_selected_key() {
+    let mut bindings = Bindings::default();
+    assert!(bindings.bind_key(Action::MoveLeft, 1, KeyCode::J));
+    assert_eq!(bindings.keys(Action::MoveLeft), [KeyCode::Left, KeyCode::J]);
+    assert!(bindings.bind_key(Action::MoveLeft, 5, KeyCode::H));
+    assert_eq!(bindings.keys(Action::MoveLeft), [KeyCode::Left, KeyCode::J, KeyCode::H]);
+    // 从其他动作上解除同一个键
+    assert!(bindings.bind_key(Action::Fire, 0, KeyCode::Left));
+    assert_eq!(bindings.keys(Action::MoveLeft), [KeyCode::J, KeyCode::H]);
+    assert_eq!(bindings.describe(Action::MoveLeft, Some(1)), "J, [H] | DPadLeft, [+]");
+}
+
+// 配置文件中无法识别的按键被跳过，同一动作的其他按键照常读取
+#[test]
+fn unknown_key_names_are_skipped() {
+    let path = std::env::temp_dir().join(format!("bindings-{}.toml", std::process::id()));
+    std::fs::write(&path, "[keys]\nJump = [\"Space\", \"NoSuchKey\"]\nDance = [\"X\"]\n").unwrap();
+    let bindings = Bindings::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(bindings.keys(Action::Jump), [KeyCode::Space]);
+    assert_eq!(bindings.keys(Action::MoveLeft), [KeyCode::Left, KeyCode::A]);
+}
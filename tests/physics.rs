@@ -0,0 +1,129 @@
+// 玩家物理的属性测试：随机的瓷砖布局、输入序列与步长，检查每一步之后的不变量
+mod common;
+
+use game::level::Level;
+use game::world::{rect_intersect, PlayerInput, World, VIRTUAL_H, VIRTUAL_W};
+use ggez::graphics::Rect;
+use proptest::prelude::*;
+
+// 浮点误差容限（像素）
+const EPS: f32 = 0.01;
+
+// 随机地图：6~12 行、25 列（与画面同宽）；最下面一行的地面可能有缺口
+fn map() -> impl Strategy<Value = Vec<String>> {
+    (6usize..=12).prop_flat_map(|rows| {
+        prop::collection::vec(prop::collection::vec(prop::bool::weighted(0.2), 25), rows - 1).prop_flat_map(move |cells| {
+            prop::collection::vec(prop::bool::weighted(0.85), 25).prop_map(move |ground| {
+                cells
+                    .iter()
+                    .chain(std::iter::once(&ground))
+                    .map(|row| row.iter().map(|&solid| if solid { '#' } else { '.' }).collect())
+                    .collect()
+            })
+        })
+    })
+}
+
+fn input() -> impl Strategy<Value = PlayerInput> {
+    (prop_oneof![Just(-1.0f32), Just(0.0), Just(1.0), -1.0f32..=1.0], any::<bool>(), any::<bool>())
+        .prop_map(|(move_x, jump, run)| PlayerInput { move_x, jump, run, fire: false })
+}
+
+// 每段输入保持若干步，比逐步随机更容易走出跑跳等连贯动作
+fn script() -> impl Strategy<Value = Vec<(PlayerInput, usize)>> {
+    prop::collection::vec((input(), 1usize..30), 1..20)
+}
+
+// 步长：从很小的步长到掉帧时的 1/20 秒
+fn dt() -> impl Strategy<Value = f32> {
+    0.001f32..0.05
+}
+
+// 与瓷砖的重叠量超过容限才算嵌入
+fn overlaps(a: &Rect, b: &Rect) -> bool {
+    let shrunk = Rect::new(a.x + EPS, a.y + EPS, a.w - 2.0 * EPS, a.h - 2.0 * EPS);
+    rect_intersect(&shrunk, b)
+}
+
+// 玩家脚下贴着瓷砖（水平方向有重叠）或站在画面底边上
+fn supported(world: &World, player: &Rect) -> bool {
+    let bottom = player.y + player.h;
+    bottom >= world.height - EPS
+        || world.tiles.iter().any(|t| (t.y - bottom).abs() <= EPS && player.x < t.x + t.w && player.x + player.w > t.x)
+}
+
+// 只保留玩家与瓷砖：去掉怪物与限时，避免死亡重生打断检查；出生点附近清空
+fn world(map: Vec<String>) -> World {
+    let level = Level { id: "fuzz".to_string(), name: "Fuzz".to_string(), time_limit: 100.0, map, special_blocks: Vec::new(), next: None };
+    let mut world = World::new(&level, VIRTUAL_W, VIRTUAL_H, 1, 3, Default::default());
+    world.monsters.clear();
+    world.time_left = f32::MAX;
+    let spawn = world.players[0].rect();
+    world.tiles.retain(|t| !rect_intersect(t, &spawn));
+    world
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(256))]
+
+    #[test]
+    fn player_never_embedded_in_tiles(map in map(), script in script(), dt in dt()) {
+        let mut world = world(map);
+        for (input, steps) in script {
+            for _ in 0..steps {
+                world.update(dt, &[input]);
+                let p = world.players[0].rect();
+                if let Some(tile) = world.tiles.iter().find(|t| overlaps(&p, t)) {
+                    prop_assert!(false, "player {:?} overlaps tile {:?}", p, tile);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn on_ground_matches_support(map in map(), script in script(), dt in dt()) {
+        let mut world = world(map);
+        for (input, steps) in script {
+            for _ in 0..steps {
+                let was_on_ground = world.players[0].on_ground;
+                world.update(dt, &[input]);
+                let player = &world.players[0];
+                let p = player.rect();
+                if player.on_ground {
+                    prop_assert!(supported(&world, &p), "on_ground but nothing under {:?}", p);
+                }
+                // 站着不动（不走不跳）时不能“离地”
+                if was_on_ground && input.move_x == 0.0 && !input.jump {
+                    prop_assert!(player.on_ground, "standing still but lost on_ground at {:?}", p);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn velocities_stay_finite(map in map(), script in script(), dt in dt()) {
+        let mut world = world(map);
+        for (input, steps) in script {
+            for _ in 0..steps {
+                world.update(dt, &[input]);
+                let player = &world.players[0];
+                prop_assert!(player.vx.is_finite() && player.vy.is_finite(), "velocity ({}, {})", player.vx, player.vy);
+                prop_assert!(player.x.is_finite() && player.y.is_finite(), "position ({}, {})", player.x, player.y);
+            }
+        }
+    }
+}
+
+// 大步长时玩家整个移出左边界，越过最左列的方块下落，之后被推回画面时曾嵌进该方块
+#[test]
+fn leaving_left_edge_does_not_embed_in_edge_tile() {
+    let mut map = vec![".".repeat(25); 5];
+    map.push(format!("#{}", ".".repeat(24)));
+    let mut world = world(map);
+    let run_left = PlayerInput { move_x: -1.0, jump: false, run: true, fire: false };
+    for _ in 0..7 {
+        world.update(0.0937, &[run_left]);
+        let p = world.players[0].rect();
+        assert!(!world.tiles.iter().any(|t| overlaps(&p, t)), "player {:?} embedded", p);
+    }
+}
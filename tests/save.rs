@@ -0,0 +1,65 @@
+use std::fs;
+
+use game::save::{self, Power, SaveData, Slot, SAVE_VERSION};
+use toml::value::Table;
+
+// 假想的第 1 -> 2 版迁移：第 2 版新增了 secrets 字段
+fn add_secrets(table: &mut Table) {
+    table.insert("secrets".to_string(), toml::Value::Array(Vec::new()));
+}
+
+const V1: &str = r#"
+version = 1
+unlocked_levels = ["1-1"]
+lives = 2
+power = "Big"
+
+[best_scores]
+1-1 = 1200
+
+[best_times]
+1-1 = 42.5
+"#;
+
+#[test]
+fn v1_save_is_migrated() {
+    let data = save::parse(V1, &[add_secrets]).expect("migrated save");
+    assert_eq!(data.version, 2);
+    assert!(data.secrets.is_empty());
+    assert_eq!(data.lives, 2);
+    assert_eq!(data.power, Power::Big);
+    assert_eq!(data.best_scores["1-1"], 1200);
+    // 没有迁移时缺少字段，无法读取
+    assert!(save::parse(V1, &[]).is_err());
+}
+
+#[test]
+fn newer_save_is_rejected() {
+    let text = V1.replace("version = 1", "version = 3");
+    assert!(save::parse(&text, &[add_secrets]).is_err());
+}
+
+#[test]
+fn slot_round_trips_through_disk() {
+    let dir = std::env::temp_dir().join(format!("save-test-{}", std::process::id()));
+    let path = save::slot_path(&dir, 0);
+    let mut data = SaveData::new("1-1");
+    data.record_level("1-1", 500, 30.0);
+    data.record_level("1-1", 300, 25.0);
+    data.unlock("1-2");
+    data.record_secret("1-1", (8, 2));
+    data.record_secret("1-1", (8, 2));
+    save::write_slot(&path, &data).unwrap();
+    let Slot::Loaded(loaded) = save::load_slot(&path) else {
+        panic!("slot should load");
+    };
+    assert_eq!(loaded.version, SAVE_VERSION);
+    assert_eq!(loaded.best_scores["1-1"], 500);
+    assert_eq!(loaded.best_times["1-1"], 25.0);
+    assert!(loaded.unlocked_levels.contains("1-1") && loaded.unlocked_levels.contains("1-2"));
+    assert_eq!(loaded.secrets_in("1-1"), 1);
+    assert_eq!(loaded.secrets_in("1-2"), 0);
+    // 原子写入不留下临时文件
+    assert!(!path.with_extension("toml.tmp").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}